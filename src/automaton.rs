@@ -0,0 +1,223 @@
+use std::array;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use grid::Grid;
+use rayon::prelude::*;
+
+use crate::utils::{MyGrid, ParseError, Point};
+
+/// Which cells count as neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Up, down, left and right
+    VonNeumann,
+    /// The 8 surrounding cells
+    Moore,
+}
+
+/// What the cells just outside of the grid look like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Boundary<T> {
+    /// Always in the given state, usually a dead cell
+    Fixed(T),
+    /// Going out of one edge comes back in by the opposite one
+    Wrapping,
+}
+
+/// Rule of the automata like Conway's game of life, written as `B3/S23`: a dead cell with
+/// 3 alive neighbours is born, and an alive one survives with 2 or 3.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeLike {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+impl LifeLike {
+    pub fn conway() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+
+    pub fn next(&self, alive: bool, alive_neighbors: usize) -> bool {
+        if alive {
+            self.survival[alive_neighbors]
+        } else {
+            self.birth[alive_neighbors]
+        }
+    }
+}
+
+impl FromStr for LifeLike {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn counts(part: &str, prefix: char) -> Result<[bool; 9], ParseError> {
+            let digits = part
+                .strip_prefix(prefix)
+                .ok_or_else(|| ParseError::new(&format!("Expected '{}' in {}", prefix, part)))?;
+            let mut res = [false; 9];
+            for c in digits.chars() {
+                match c.to_digit(10) {
+                    Some(n) if n < 9 => res[n as usize] = true,
+                    _ => return Err(ParseError::new(&format!("Invalid count '{}'", c))),
+                }
+            }
+            Ok(res)
+        }
+        let (birth, survival) = s
+            .split_once('/')
+            .ok_or_else(|| ParseError::new(&format!("Expected B../S.. but got {}", s)))?;
+        Ok(LifeLike {
+            birth: counts(birth, 'B')?,
+            survival: counts(survival, 'S')?,
+        })
+    }
+}
+
+/// Grid where every cell changes at the same time, depending on its neighbours.
+///
+/// `rule` gets the current state of a cell and the states of its neighbours.
+pub struct Automaton<T, R> {
+    pub grid: MyGrid<T>,
+    rule: R,
+    neighborhood: Neighborhood,
+    boundary: Boundary<T>,
+    /// Cells that never change
+    pinned: HashMap<Point, T>,
+}
+
+impl<T, R> Automaton<T, R>
+where
+    T: Clone + Send + Sync,
+    R: Fn(&T, &[&T]) -> T + Sync,
+{
+    /// With the Moore neighbourhood and no pinned cells
+    pub fn new(grid: MyGrid<T>, rule: R, boundary: Boundary<T>) -> Self {
+        Automaton {
+            grid,
+            rule,
+            neighborhood: Neighborhood::Moore,
+            boundary,
+            pinned: HashMap::new(),
+        }
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Sets the cell at `point` to `value`, and keeps it like that forever
+    pub fn pin(mut self, point: Point, value: T) -> Self {
+        self.grid[&point] = value.clone();
+        self.pinned.insert(point, value);
+        self
+    }
+
+    /// The 8 surrounding cells, orthogonal ones first, in an array so nothing gets
+    /// allocated for every cell
+    fn neighbors(&self, point: &Point) -> [&T; 8] {
+        array::from_fn(|i| {
+            let (dx, dy) = Point::ALL_OFFSETS[i];
+            let p = Point::new(point.x + dx, point.y + dy);
+            match &self.boundary {
+                Boundary::Fixed(outside) => self.grid.get(&p).unwrap_or(outside),
+                Boundary::Wrapping => &self.grid[&self.grid.wrap(&p)],
+            }
+        })
+    }
+
+    pub fn step(&mut self) {
+        let cols = self.grid.cols();
+        let len = match self.neighborhood {
+            Neighborhood::VonNeumann => 4,
+            Neighborhood::Moore => 8,
+        };
+        let cells = (0..self.grid.rows() * cols)
+            .into_par_iter()
+            .map(|i| {
+                let point = Point::from((i / cols, i % cols));
+                match self.pinned.get(&point) {
+                    Some(value) => value.clone(),
+                    None => (self.rule)(&self.grid[&point], &self.neighbors(&point)[..len]),
+                }
+            })
+            .collect();
+        self.grid = MyGrid(Grid::from_vec(cells, cols));
+    }
+
+    pub fn run(&mut self, steps: usize) -> &MyGrid<T> {
+        for _ in 0..steps {
+            self.step();
+        }
+        &self.grid
+    }
+}
+
+#[test]
+fn test_automaton() {
+    use indoc::indoc;
+
+    let life = LifeLike::conway();
+    let rule = |x: &char, ns: &[&char]| {
+        let alive = ns.iter().filter(|c| ***c == '#').count();
+        if life.next(*x == '#', alive) {
+            '#'
+        } else {
+            '.'
+        }
+    };
+    let blinker = MyGrid::cparse(indoc! {"
+        .....
+        ..#..
+        ..#..
+        ..#..
+        .....
+    "});
+    let mut automaton = Automaton::new(blinker.clone(), rule, Boundary::Fixed('.'));
+    automaton.step();
+    assert_eq!(
+        automaton.grid,
+        MyGrid::cparse(indoc! {"
+            .....
+            .....
+            .###.
+            .....
+            .....
+        "})
+    );
+    assert_eq!(automaton.run(1), &blinker);
+
+    // The glider comes back to the same place after going around the whole grid
+    let glider = MyGrid::cparse(indoc! {"
+        .#...
+        ..#..
+        ###..
+        .....
+        .....
+    "});
+    let mut automaton = Automaton::new(glider.clone(), rule, Boundary::Wrapping);
+    assert_eq!(automaton.run(20), &glider);
+
+    // A lonely cell would die, unless it is pinned
+    let empty = MyGrid::cparse("...\n...\n...\n");
+    let mut automaton =
+        Automaton::new(empty, rule, Boundary::Fixed('.')).pin(Point::new(0, 0), '#');
+    automaton.run(2);
+    assert_eq!(automaton.grid[&Point::new(0, 0)], '#');
+    assert_eq!(automaton.grid.iter().filter(|c| **c == '#').count(), 1);
+
+    let rule = |x: &u8, ns: &[&u8]| ns.iter().map(|n| **n).max().unwrap().max(*x);
+    let grid = MyGrid(Grid::from_vec(vec![0, 0, 0, 0, 9, 0, 0, 0, 0], 3));
+    let mut automaton =
+        Automaton::new(grid, rule, Boundary::Fixed(0)).with_neighborhood(Neighborhood::VonNeumann);
+    automaton.step();
+    assert_eq!(
+        automaton.grid.iter().copied().collect::<Vec<_>>(),
+        vec![0, 9, 0, 9, 9, 9, 0, 9, 0]
+    );
+
+    assert!("B36/S23".parse::<LifeLike>().unwrap().next(false, 6));
+    assert!("B3S23".parse::<LifeLike>().is_err());
+    assert!("B9/S23".parse::<LifeLike>().is_err());
+}
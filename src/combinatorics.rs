@@ -0,0 +1,141 @@
+//! Ways of adding up to a target: choosing some items that add up to it (subset sum),
+//! without listing all of them, and splitting it into parts (compositions).
+//!
+//! Items are chosen at most once, and equal items are still different choices.
+
+use std::iter::from_fn;
+
+/// `ways[k]` is the number of ways of choosing `k` of the `items` so they add up to `target`
+pub fn count_subsets_by_size(items: &[u32], target: u32) -> Vec<usize> {
+    let target = target as usize;
+    // ways[k][s] is the number of ways of choosing k of the items seen so far adding up to s
+    let mut ways = vec![vec![0; target + 1]; items.len() + 1];
+    ways[0][0] = 1;
+    for (i, &item) in items.iter().enumerate() {
+        let item = item as usize;
+        // Going down, so ways[k - 1] doesn't include the current item yet
+        for k in (1..=i + 1).rev() {
+            for s in item..=target {
+                ways[k][s] += ways[k - 1][s - item];
+            }
+        }
+    }
+    ways.into_iter().map(|w| w[target]).collect()
+}
+
+/// Number of ways of choosing some of the `items` so they add up to `target`
+pub fn count_subsets(items: &[u32], target: u32) -> usize {
+    count_subsets_by_size(items, target).into_iter().sum()
+}
+
+/// Indices of the `items` of every way of adding up to `target`, one at a time
+pub fn subsets_with_sum(items: &[u32], target: u32) -> impl Iterator<Item = Vec<usize>> + '_ {
+    let target = target as usize;
+    // reachable[i][s] is whether some of items[i..] add up to s, so the search never gets
+    // into a branch without solutions
+    let mut reachable = vec![vec![false; target + 1]; items.len() + 1];
+    reachable[items.len()][0] = true;
+    for i in (0..items.len()).rev() {
+        let item = items[i] as usize;
+        for s in 0..=target {
+            reachable[i][s] = reachable[i + 1][s] || (s >= item && reachable[i + 1][s - item]);
+        }
+    }
+    // The next item to decide, what is left to add up, and the items taken until now
+    let mut stack = vec![];
+    if reachable[0][target] {
+        stack.push((0, target, vec![]));
+    }
+    from_fn(move || {
+        while let Some((i, left, chosen)) = stack.pop() {
+            if i == items.len() {
+                return Some(chosen);
+            }
+            let item = items[i] as usize;
+            if reachable[i + 1][left] {
+                stack.push((i + 1, left, chosen.clone()));
+            }
+            if left >= item && reachable[i + 1][left - item] {
+                let mut chosen = chosen;
+                chosen.push(i);
+                stack.push((i + 1, left - item, chosen));
+            }
+        }
+        None
+    })
+}
+
+/// Every way of splitting `total` into `parts` numbers (that can be 0), in lexicographic
+/// order, one at a time
+pub fn compositions(parts: usize, total: u32) -> impl Iterator<Item = Vec<u32>> {
+    // The first parts count like an odometer, as long as they don't go over the total, and
+    // the last part takes the rest
+    let mut next = match parts {
+        0 => (total == 0).then(Vec::new),
+        _ => {
+            let mut first = vec![0; parts];
+            first[parts - 1] = total;
+            Some(first)
+        }
+    };
+    from_fn(move || {
+        let curr = next.take()?;
+        let mut new = curr.clone();
+        let last = parts.saturating_sub(1);
+        for i in (0..last).rev() {
+            if new[last] > 0 {
+                new[i] += 1;
+                new[last] -= 1;
+                next = Some(new);
+                break;
+            }
+            new[last] += new[i];
+            new[i] = 0;
+        }
+        Some(curr)
+    })
+}
+
+#[test]
+fn test_combinatorics() {
+    use itertools::Itertools;
+
+    let items = [20, 15, 10, 5, 5];
+    assert_eq!(count_subsets_by_size(&items, 25), vec![0, 0, 3, 1, 0, 0]);
+    assert_eq!(count_subsets(&items, 25), 4);
+    assert_eq!(count_subsets(&items, 0), 1);
+    assert_eq!(count_subsets(&items, 100), 0);
+
+    let subsets = subsets_with_sum(&items, 25).collect_vec();
+    assert_eq!(
+        subsets,
+        vec![vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]]
+    );
+    assert!(subsets_with_sum(&items, 100).next().is_none());
+    assert_eq!(
+        subsets_with_sum(&[], 0).collect_vec(),
+        vec![Vec::<usize>::new()]
+    );
+
+    // It is lazy, the first one is found without going through the other 155 million
+    assert_eq!(
+        subsets_with_sum(&[1; 30], 15).next(),
+        Some((0..15).collect())
+    );
+
+    assert_eq!(
+        compositions(3, 2).collect_vec(),
+        vec![
+            vec![0, 0, 2],
+            vec![0, 1, 1],
+            vec![0, 2, 0],
+            vec![1, 0, 1],
+            vec![1, 1, 0],
+            vec![2, 0, 0]
+        ]
+    );
+    assert_eq!(compositions(4, 100).count(), 176_851);
+    assert_eq!(compositions(1, 7).collect_vec(), vec![vec![7]]);
+    assert_eq!(compositions(0, 0).count(), 1);
+    assert_eq!(compositions(0, 3).count(), 0);
+}
@@ -0,0 +1,212 @@
+//! Small parser combinators, for inputs with nested structures.
+//!
+//! A parser is any `Fn(&str) -> PResult<T>`, that returns the value and the input that is
+//! left after it. Recursive structures are parsed with a normal `fn` that uses itself
+//! inside of the combinators.
+
+use std::str::FromStr;
+
+use crate::utils::ParseError;
+
+/// Where and why a parser failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// Length of the input that was left, to know the position once the whole input is known
+    rest_len: usize,
+    expected: String,
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure>;
+
+fn fail<'a, T>(rest: &str, expected: &str) -> PResult<'a, T> {
+    Err(Failure {
+        rest_len: rest.len(),
+        expected: expected.to_string(),
+    })
+}
+
+/// Runs `parser` on the whole `input`, it is an error if something is left
+pub fn parse_all<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    input: &'a str,
+) -> Result<T, ParseError> {
+    let located = |failure: Failure| {
+        let pos = input.len() - failure.rest_len;
+        let context: String = input[pos..].chars().take(10).collect();
+        ParseError::new(&format!(
+            "Expected {} at position {}, but found '{}'",
+            failure.expected, pos, context
+        ))
+    };
+    match parser(input) {
+        Ok((value, "")) => Ok(value),
+        Ok((_, rest)) => Err(located(Failure {
+            rest_len: rest.len(),
+            expected: "the end".to_string(),
+        })),
+        Err(failure) => Err(located(failure)),
+    }
+}
+
+pub fn literal<'a>(lit: &'static str) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(lit) {
+        Some(rest) => Ok((&input[..lit.len()], rest)),
+        None => fail(input, &format!("'{}'", lit)),
+    }
+}
+
+/// Integer, with an optional `-` in front
+pub fn number<'a, T: FromStr>() -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input: &'a str| {
+        let sign = usize::from(input.starts_with('-'));
+        let digits = input[sign..].bytes().take_while(u8::is_ascii_digit).count();
+        match input[..sign + digits].parse() {
+            Ok(n) if digits > 0 => Ok((n, &input[sign + digits..])),
+            _ => fail(input, "a number"),
+        }
+    }
+}
+
+/// Text between double quotes, without any escaping
+pub fn quoted<'a>() -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input: &'a str| {
+        let Some(rest) = input.strip_prefix('"') else {
+            return fail(input, "'\"'");
+        };
+        match rest.find('"') {
+            Some(end) => Ok((&rest[..end], &rest[end + 1..])),
+            None => fail(input, "a closing '\"'"),
+        }
+    }
+}
+
+pub fn map<'a, A, B>(
+    parser: impl Fn(&'a str) -> PResult<'a, A>,
+    f: impl Fn(A) -> B,
+) -> impl Fn(&'a str) -> PResult<'a, B> {
+    move |input| parser(input).map(|(value, rest)| (f(value), rest))
+}
+
+/// Tries `first`, and if it fails, `second`. If both fail, the error is the one that
+/// went further into the input.
+pub fn or<'a, T>(
+    first: impl Fn(&'a str) -> PResult<'a, T>,
+    second: impl Fn(&'a str) -> PResult<'a, T>,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input| {
+        let a = match first(input) {
+            Ok(res) => return Ok(res),
+            Err(a) => a,
+        };
+        second(input).map_err(|b| match a.rest_len.cmp(&b.rest_len) {
+            std::cmp::Ordering::Less => a,
+            std::cmp::Ordering::Greater => b,
+            std::cmp::Ordering::Equal => Failure {
+                rest_len: a.rest_len,
+                expected: format!("{} or {}", a.expected, b.expected),
+            },
+        })
+    }
+}
+
+/// Both parsers, one after the other
+pub fn pair<'a, A, B>(
+    first: impl Fn(&'a str) -> PResult<'a, A>,
+    second: impl Fn(&'a str) -> PResult<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, (A, B)> {
+    move |input| {
+        let (a, rest) = first(input)?;
+        let (b, rest) = second(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+/// The value of `parser`, between `open` and `close`
+pub fn delimited<'a, T>(
+    open: &'static str,
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    close: &'static str,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input| {
+        let (_, rest) = literal(open)(input)?;
+        let (value, rest) = parser(rest)?;
+        let (_, rest) = literal(close)(rest)?;
+        Ok((value, rest))
+    }
+}
+
+/// Zero or more values, with `separator` between them
+pub fn separated<'a, T>(
+    parser: impl Fn(&'a str) -> PResult<'a, T>,
+    separator: &'static str,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<T>> {
+    move |input| {
+        let mut values = vec![];
+        let mut rest = match parser(input) {
+            Ok((value, rest)) => {
+                values.push(value);
+                rest
+            }
+            Err(_) => return Ok((values, input)),
+        };
+        while let Ok((_, after_sep)) = literal(separator)(rest) {
+            let (value, after_value) = parser(after_sep)?;
+            values.push(value);
+            rest = after_value;
+        }
+        Ok((values, rest))
+    }
+}
+
+/// Same as `parser`, ignoring the whitespace around it
+pub fn ws<'a, T>(parser: impl Fn(&'a str) -> PResult<'a, T>) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input: &'a str| {
+        let (value, rest) = parser(input.trim_start())?;
+        Ok((value, rest.trim_start()))
+    }
+}
+
+#[test]
+fn test_combinators() {
+    #[derive(Debug, PartialEq)]
+    enum Tree {
+        Leaf(i32),
+        Node(Vec<Tree>),
+    }
+
+    fn tree(input: &str) -> PResult<'_, Tree> {
+        ws(or(
+            map(number(), Tree::Leaf),
+            map(delimited("(", separated(tree, ","), ")"), Tree::Node),
+        ))(input)
+    }
+
+    assert_eq!(
+        parse_all(tree, "(1, (-2,3) ,())").unwrap(),
+        Tree::Node(vec![
+            Tree::Leaf(1),
+            Tree::Node(vec![Tree::Leaf(-2), Tree::Leaf(3)]),
+            Tree::Node(vec![]),
+        ])
+    );
+    assert_eq!(
+        parse_all(tree, "(1,(2,x))").unwrap_err().to_string(),
+        "Expected a number or '(' at position 6, but found 'x))'"
+    );
+    assert_eq!(
+        parse_all(tree, "(1,2").unwrap_err().to_string(),
+        "Expected ')' at position 4, but found ''"
+    );
+    assert_eq!(
+        parse_all(tree, "(1)(2)").unwrap_err().to_string(),
+        "Expected the end at position 3, but found '(2)'"
+    );
+
+    let key_value = pair(
+        quoted(),
+        map(pair(literal(":"), number::<u8>()), |(_, n)| n),
+    );
+    assert_eq!(key_value(r#""a":12,"#), Ok((("a", 12), ",")));
+    assert!(parse_all(number::<u8>(), "-").is_err());
+    assert!(parse_all(number::<u8>(), "256").is_err());
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Finds when the sequence `initial, step(initial), step(step(initial)), ...` starts repeating.
+///
+/// Returns `(prefix, len)`: the state at index `prefix` is the first one to be seen again,
+/// `len` steps later. Keeps every state in memory, see [`find_cycle_brent`] for big states.
+pub fn find_cycle<S>(initial: S, step: impl FnMut(&S) -> S) -> (usize, usize)
+where
+    S: Hash + Eq + Clone,
+{
+    let (history, prefix) = run_until_repeat(initial, step, usize::MAX);
+    let prefix = prefix.expect("The sequence should repeat at some point");
+    (prefix, history.len() - prefix)
+}
+
+/// Same as [`find_cycle`], but only keeps two states in memory at a time, at the cost of
+/// calling `step` a few more times (Brent's algorithm).
+pub fn find_cycle_brent<S>(initial: S, mut step: impl FnMut(&S) -> S) -> (usize, usize)
+where
+    S: Eq + Clone,
+{
+    // Find the length, by moving the tortoise to the hare every power of two
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    // With the hare `len` steps ahead, they meet at the start of the cycle
+    let mut prefix = 0;
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    (prefix, len)
+}
+
+/// Index of the first state that is the same as the `n`th one
+pub fn first_equivalent((prefix, len): (usize, usize), n: usize) -> usize {
+    if n < prefix {
+        n
+    } else {
+        prefix + (n - prefix) % len
+    }
+}
+
+/// The state after calling `step` `n` times, without doing it more than needed
+pub fn nth_state<S>(initial: S, step: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: Hash + Eq + Clone,
+{
+    let (mut history, prefix) = run_until_repeat(initial, step, n);
+    let idx = match prefix {
+        Some(prefix) => first_equivalent((prefix, history.len() - prefix), n),
+        None => n,
+    };
+    history.swap_remove(idx)
+}
+
+/// Same as [`nth_state`], but using [`find_cycle_brent`]. The states before the cycle
+/// are calculated again, as they are not saved.
+pub fn nth_state_brent<S>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: Eq + Clone,
+{
+    let cycle = find_cycle_brent(initial.clone(), &mut step);
+    (0..first_equivalent(cycle, n)).fold(initial, |state, _| step(&state))
+}
+
+/// All the states until one repeats or `n` steps are made. Also returns the index of
+/// the repeated state, if that is what stopped the loop.
+fn run_until_repeat<S>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    n: usize,
+) -> (Vec<S>, Option<usize>)
+where
+    S: Hash + Eq + Clone,
+{
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    let mut state = initial;
+    while history.len() <= n {
+        if let Some(prev) = seen.get(&state) {
+            return (history, Some(*prev));
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+    (history, None)
+}
+
+#[test]
+fn test_cycles() {
+    // 0, 1, 2, 3, 4, 2, 3, 4, ...
+    let step = |x: &usize| [1, 2, 3, 4, 2][*x];
+    assert_eq!(find_cycle(0, step), (2, 3));
+    assert_eq!(find_cycle_brent(0, step), (2, 3));
+    assert_eq!(find_cycle(0, |x| (x + 1) % 5), (0, 5));
+    assert_eq!(find_cycle_brent(0, |x| (x + 1) % 5), (0, 5));
+    assert_eq!(find_cycle(7, |_| 7), (0, 1));
+    assert_eq!(find_cycle_brent(7, |_| 7), (0, 1));
+
+    assert_eq!(nth_state(0, step, 0), 0);
+    assert_eq!(nth_state(0, step, 4), 4);
+    assert_eq!(nth_state(0, step, 5), 2);
+    assert_eq!(nth_state(0, step, 1_000_000_000), 4);
+    assert_eq!(nth_state_brent(0, step, 1_000_000_000), 4);
+    assert_eq!(nth_state(0, |x| x + 1, 1000), 1000);
+}
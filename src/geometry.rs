@@ -0,0 +1,114 @@
+use num::integer::gcd;
+
+use crate::utils::Point;
+
+/// Closed polygon whose vertices are all on integer coordinates.
+///
+/// The last vertex is connected back to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Polygon { vertices }
+    }
+
+    /// Polygon from a loop traced one cell at a time, keeping only the corners
+    pub fn from_path(path: &[Point]) -> Self {
+        let n = path.len();
+        let vertices = (0..n)
+            .filter(|&i| {
+                let prev = &path[(i + n - 1) % n];
+                let curr = &path[i];
+                let next = &path[(i + 1) % n];
+                // Not a corner if the cross product of the two edges is 0
+                (curr.x - prev.x) * (next.y - curr.y) != (curr.y - prev.y) * (next.x - curr.x)
+            })
+            .map(|i| path[i].clone())
+            .collect();
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Twice the area, so it is always an integer (shoelace formula).
+    ///
+    /// Positive if the vertices go clockwise (remember that y grows downwards).
+    pub fn signed_double_area(&self) -> isize {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.signed_double_area().unsigned_abs() as f64 / 2.0
+    }
+
+    /// Number of integer points on the edges
+    pub fn boundary_points(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| gcd(a.x - b.x, a.y - b.y).unsigned_abs())
+            .sum()
+    }
+
+    /// Number of integer points strictly inside the polygon (Pick's theorem). Polygons
+    /// without any area, like a single segment, have none.
+    pub fn interior_points(&self) -> usize {
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+        let double_area = self.signed_double_area().abs();
+        ((double_area + 2 - self.boundary_points() as isize) / 2).max(0) as usize
+    }
+}
+
+#[test]
+fn test_polygon() {
+    let square = Polygon::new(vec![
+        Point::new(0, 0),
+        Point::new(4, 0),
+        Point::new(4, 4),
+        Point::new(0, 4),
+    ]);
+    assert_eq!(square.signed_double_area(), 32);
+    assert_eq!(square.area(), 16.0);
+    assert_eq!(square.boundary_points(), 16);
+    assert_eq!(square.interior_points(), 9);
+
+    let triangle = Polygon::new(vec![Point::new(0, 0), Point::new(0, 3), Point::new(3, 0)]);
+    assert_eq!(triangle.signed_double_area(), -9);
+    assert_eq!(triangle.area(), 4.5);
+    assert_eq!(triangle.boundary_points(), 9);
+    assert_eq!(triangle.interior_points(), 1);
+
+    let path = [
+        (0, 0),
+        (0, 1),
+        (0, 2),
+        (1, 2),
+        (2, 2),
+        (2, 1),
+        (2, 0),
+        (1, 0),
+    ];
+    let from_path = Polygon::from_path(&path.map(Point::from));
+    assert_eq!(
+        from_path.vertices(),
+        [(0, 0), (0, 2), (2, 2), (2, 0)].map(Point::from)
+    );
+    assert_eq!(from_path.interior_points(), 1);
+
+    assert_eq!(Polygon::new(vec![]).interior_points(), 0);
+    let segment = Polygon::new(vec![Point::new(0, 0), Point::new(4, 0)]);
+    assert_eq!(segment.interior_points(), 0);
+    let flat = Polygon::new(vec![Point::new(0, 0), Point::new(2, 0), Point::new(4, 0)]);
+    assert_eq!(flat.interior_points(), 0);
+}
@@ -1,7 +1,16 @@
 #![allow(dead_code)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison, clippy::useless_vec))]
 
 extern crate core;
 
+mod automaton;
+mod combinatorics;
+mod combinators;
+mod cycles;
+mod geometry;
+mod math;
+mod search;
+mod tsp;
 mod utils;
 mod year2015;
 mod year2016;
@@ -0,0 +1,127 @@
+/// `(g, x, y)` where `g` is the greatest common divisor of `a` and `b` (never negative), and
+/// `a * x + b * y == g`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` such that `a * x` is 1 modulo `m`, if `a` and `m` are coprime and `m` is
+/// positive
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base` to the power of `exp`, modulo `m`, that must be positive
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    assert!(m > 0, "The modulus must be positive, but it is {}", m);
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut res = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = res * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    res as i64
+}
+
+/// The result doesn't fit in an `i64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+/// Solves `x ≡ r (mod m)` for all the `(r, m)` pairs at the same time (Chinese remainder
+/// theorem). The moduli don't need to be coprime.
+///
+/// Returns `(x, lcm)`, where `x` is the smallest solution that is not negative and every
+/// other solution is `x` plus a multiple of the `lcm` of the moduli. `None` if the
+/// congruences contradict each other or some modulus is not positive, and an error if the
+/// `lcm` is too big.
+pub fn crt(congruences: &[(i64, i64)]) -> Result<Option<(i64, i64)>, Overflow> {
+    let mut res: (i128, i128) = (0, 1);
+    for &(r, m) in congruences {
+        if m <= 0 {
+            return Ok(None);
+        }
+        let (x, lcm) = res;
+        let (g, p, _) = extended_gcd((lcm % m as i128) as i64, m);
+        let diff = r as i128 - x;
+        if diff % g as i128 != 0 {
+            return Ok(None);
+        }
+        // x + lcm * k ≡ r (mod m), so k ≡ diff / g * p (mod m / g)
+        let step = m as i128 / g as i128;
+        let k = (diff / g as i128 % step * p as i128).rem_euclid(step);
+        let new_lcm = lcm * step;
+        if new_lcm > i64::MAX as i128 {
+            return Err(Overflow);
+        }
+        res = ((x + lcm * k).rem_euclid(new_lcm), new_lcm);
+    }
+    Ok(Some((res.0 as i64, res.1 as i64)))
+}
+
+/// Biggest number whose square is not bigger than `n`
+pub fn isqrt(n: u64) -> u64 {
+    // The float is close enough to only need a couple of adjustments
+    let mut x = (n as f64).sqrt() as u64;
+    while x.checked_mul(x).is_none_or(|sq| sq > n) {
+        x -= 1;
+    }
+    while (x + 1).checked_mul(x + 1).is_some_and(|sq| sq <= n) {
+        x += 1;
+    }
+    x
+}
+
+#[test]
+fn test_math() {
+    assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+    assert_eq!(extended_gcd(-4, 6), (2, 1, 1));
+    assert_eq!(extended_gcd(0, 5), (5, 0, 1));
+
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(-3, 11), Some(7));
+    assert_eq!(mod_inverse(6, 9), None);
+    assert_eq!(mod_inverse(3, 0), None);
+
+    assert_eq!(mod_pow(4, 13, 497), 445);
+    assert_eq!(mod_pow(-2, 3, 5), 2);
+    assert_eq!(mod_pow(7, 0, 1), 0);
+    assert_eq!(mod_pow(i64::MAX - 1, 1 << 40, i64::MAX), 1);
+    assert!(std::panic::catch_unwind(|| mod_pow(2, 3, 0)).is_err());
+
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Ok(Some((23, 105))));
+    assert_eq!(crt(&[(3, 4), (5, 6)]), Ok(Some((11, 12))));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), Ok(None));
+    assert_eq!(crt(&[(0, 6), (0, 4)]), Ok(Some((0, 12))));
+    assert_eq!(crt(&[(-1, 5)]), Ok(Some((4, 5))));
+    assert_eq!(crt(&[]), Ok(Some((0, 1))));
+    assert_eq!(crt(&[(1, 0)]), Ok(None));
+    assert_eq!(crt(&[(2, 3), (1, -5)]), Ok(None));
+    assert_eq!(crt(&[(0, 1 << 40), (1, (1 << 40) + 1)]), Err(Overflow));
+    assert_eq!(crt(&[(5, 1 << 40), (5, 1 << 40)]), Ok(Some((5, 1 << 40))));
+
+    assert_eq!(isqrt(0), 0);
+    assert_eq!(isqrt(15), 3);
+    assert_eq!(isqrt(16), 4);
+    assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+    assert_eq!(isqrt(999_999_999_999_999_999), 999_999_999);
+}
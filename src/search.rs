@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Everything found while exploring a graph from a set of starting states
+#[derive(Debug)]
+pub struct SearchResult<S, C> {
+    /// Lowest cost to reach each of the visited states
+    pub costs: HashMap<S, C>,
+    /// Previous state in the best path to each visited state. Starting states have none
+    pub predecessors: HashMap<S, S>,
+    /// First state that satisfied the goal, if any
+    pub goal: Option<S>,
+}
+
+impl<S, C> SearchResult<S, C>
+where
+    S: Eq + Hash + Clone,
+{
+    fn new() -> Self {
+        SearchResult {
+            costs: HashMap::new(),
+            predecessors: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn cost(&self, state: &S) -> Option<&C> {
+        self.costs.get(state)
+    }
+
+    pub fn goal_cost(&self) -> Option<&C> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    /// States from the start up to (and including) `state`
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.costs.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        while let Some(prev) = self.predecessors.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Breadth first search, where every move costs 1.
+///
+/// Stops as soon as a state satisfies `is_goal`. Use `|_| false` to explore everything reachable.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, usize>
+where
+    S: Eq + Hash + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut res = SearchResult::new();
+    let mut to_visit = VecDeque::new();
+    for start in starts {
+        res.costs.insert(start.clone(), 0);
+        to_visit.push_back((start, 0));
+    }
+
+    while let Some((state, cost)) = to_visit.pop_front() {
+        if is_goal(&state) {
+            res.goal = Some(state);
+            break;
+        }
+        for next in successors(&state) {
+            if !res.costs.contains_key(&next) {
+                res.costs.insert(next.clone(), cost + 1);
+                res.predecessors.insert(next.clone(), state.clone());
+                to_visit.push_back((next, cost + 1));
+            }
+        }
+    }
+    res
+}
+
+/// Shortest paths when each move has a different (non negative) cost.
+///
+/// Same as [`astar`] without any heuristic.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+struct Candidate<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Candidate<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so the heap gives the lowest priority first
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Candidate<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Candidate<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Candidate<S, C> {}
+
+/// Shortest path guided by `heuristic`, that estimates the remaining cost to the goal.
+///
+/// The heuristic must never overestimate, otherwise the path found may not be the best one.
+/// Stops as soon as a state satisfies `is_goal`.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S, C>
+where
+    S: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut res = SearchResult::new();
+    let mut open_set = BinaryHeap::new();
+    for start in starts {
+        res.costs.insert(start.clone(), C::default());
+        open_set.push(Candidate {
+            priority: heuristic(&start),
+            cost: C::default(),
+            state: start,
+        });
+    }
+
+    while let Some(Candidate { cost, state, .. }) = open_set.pop() {
+        if cost > res.costs[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            res.goal = Some(state);
+            break;
+        }
+        for (next, step) in successors(&state) {
+            let new_cost = cost + step;
+            if res.costs.get(&next).is_none_or(|old| new_cost < *old) {
+                res.costs.insert(next.clone(), new_cost);
+                res.predecessors.insert(next.clone(), state.clone());
+                open_set.push(Candidate {
+                    priority: new_cost + heuristic(&next),
+                    cost: new_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+    res
+}
+
+#[test]
+fn test_search() {
+    use crate::utils::Point;
+
+    // Walls on x == 2, except for y == 4
+    let successors = |p: &Point| {
+        p.neighbors()
+            .into_iter()
+            .filter(|n| (0..5).contains(&n.x) && (0..5).contains(&n.y))
+            .filter(|n| n.x != 2 || n.y == 4)
+    };
+    let start = Point::new(0, 0);
+    let end = Point::new(4, 0);
+
+    let res = bfs([start.clone()], successors, |p| *p == end);
+    assert_eq!(res.goal_cost(), Some(&12));
+    let path = res.goal_path().unwrap();
+    assert_eq!(path.len(), 13);
+    assert_eq!(path[0], start);
+    assert!(path.contains(&Point::new(2, 4)));
+
+    let weighted = |p: &Point| successors(p).map(|n| (n, 2));
+    let res = dijkstra([start.clone()], weighted, |p| *p == end);
+    assert_eq!(res.goal_cost(), Some(&24));
+
+    let res = astar(
+        [start.clone()],
+        weighted,
+        |p| 2 * p.manhathan_dist(&end),
+        |p| *p == end,
+    );
+    assert_eq!(res.goal_cost(), Some(&24));
+    assert_eq!(res.goal_path().unwrap().len(), 13);
+
+    let res = bfs([start], successors, |_| false);
+    assert_eq!(res.goal, None);
+    assert_eq!(res.costs.len(), 21);
+}
@@ -0,0 +1,159 @@
+//! Exact travelling salesman solutions, with the Held–Karp dynamic programming.
+//!
+//! The nodes are the indices of a dense matrix, where `dist[a][b]` is the cost of going
+//! from `a` to `b`, or `None` if it is not possible. It takes `O(2ⁿ n²)` time, so it is only usable up to ~20 nodes, but it
+//! is much better than trying all the `n!` orders.
+
+use std::ops::Add;
+
+/// Whether the best route is the cheapest or the most expensive one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Min,
+    Max,
+}
+
+impl Goal {
+    fn is_better<C: Ord>(self, a: C, b: C) -> bool {
+        match self {
+            Goal::Min => a < b,
+            Goal::Max => a > b,
+        }
+    }
+}
+
+/// Best route found, with the nodes in the order they are visited
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<C> {
+    pub cost: C,
+    pub order: Vec<usize>,
+}
+
+/// Best route that visits every node once, starting and ending anywhere.
+/// `None` if there are no nodes, or no such route.
+pub fn best_path<C>(dist: &[Vec<Option<C>>], goal: Goal) -> Option<Tour<C>>
+where
+    C: Ord + Copy + Default + Add<Output = C>,
+{
+    held_karp(dist, goal, false)
+}
+
+/// Best route that visits every node once and goes back to the first one. The order always
+/// starts at node 0. `None` if there are no nodes, or no such route.
+pub fn best_cycle<C>(dist: &[Vec<Option<C>>], goal: Goal) -> Option<Tour<C>>
+where
+    C: Ord + Copy + Default + Add<Output = C>,
+{
+    held_karp(dist, goal, true)
+}
+
+fn held_karp<C>(dist: &[Vec<Option<C>>], goal: Goal, cycle: bool) -> Option<Tour<C>>
+where
+    C: Ord + Copy + Default + Add<Output = C>,
+{
+    let n = dist.len();
+    let full = (1 << n) - 1;
+    // best[visited][last] is the best cost of visiting the `visited` set of nodes, ending
+    // at `last`, and prev[visited][last] is the node before it
+    let mut best: Vec<Vec<Option<C>>> = vec![vec![None; n]; full + 1];
+    let mut prev = vec![vec![0; n]; full + 1];
+    // A cycle can start anywhere, so it always starts at 0
+    let starts = if cycle { 0..n.min(1) } else { 0..n };
+    for start in starts {
+        best[1 << start][start] = Some(C::default());
+    }
+    for visited in 1..=full {
+        for last in 0..n {
+            let Some(cost) = best[visited][last] else {
+                continue;
+            };
+            for next in (0..n).filter(|next| visited & (1 << next) == 0) {
+                let Some(step) = dist[last][next] else {
+                    continue;
+                };
+                let new_cost = cost + step;
+                let entry = &mut best[visited | (1 << next)][next];
+                if entry.is_none_or(|old| goal.is_better(new_cost, old)) {
+                    *entry = Some(new_cost);
+                    prev[visited | (1 << next)][next] = last;
+                }
+            }
+        }
+    }
+    let (mut last, cost) = (0..n)
+        .filter_map(|last| {
+            let back = if cycle { dist[last][0]? } else { C::default() };
+            Some((last, best[full][last]? + back))
+        })
+        .reduce(|a, b| if goal.is_better(b.1, a.1) { b } else { a })?;
+
+    let mut order = vec![last];
+    let mut visited = full;
+    while visited != 1 << last {
+        let before = prev[visited][last];
+        visited ^= 1 << last;
+        last = before;
+        order.push(last);
+    }
+    order.reverse();
+    Some(Tour { cost, order })
+}
+
+#[test]
+fn test_tsp() {
+    use itertools::Itertools;
+
+    let dist = [
+        [0, 3, 9, 4, 7],
+        [2, 0, 6, 1, 8],
+        [5, 7, 0, 3, 2],
+        [6, 4, 8, 0, 5],
+        [1, 9, 3, 6, 0],
+    ];
+    let matrix = dist.map(|row| row.map(Some).to_vec()).to_vec();
+    let path_cost = |order: &[usize]| {
+        order
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| dist[*a][*b])
+            .sum::<i32>()
+    };
+    let cycle_cost = |order: &[usize]| path_cost(order) + dist[*order.last().unwrap()][order[0]];
+    let brute_force = |cost: &dyn Fn(&[usize]) -> i32, goal| {
+        let costs = (0..dist.len()).permutations(dist.len()).map(|p| cost(&p));
+        match goal {
+            Goal::Min => costs.min().unwrap(),
+            Goal::Max => costs.max().unwrap(),
+        }
+    };
+
+    for goal in [Goal::Min, Goal::Max] {
+        let path = best_path(&matrix, goal).unwrap();
+        assert_eq!(path.cost, brute_force(&path_cost, goal));
+        assert_eq!(path.cost, path_cost(&path.order));
+        assert!(path.order.iter().copied().sorted().eq(0..dist.len()));
+
+        let cycle = best_cycle(&matrix, goal).unwrap();
+        assert_eq!(cycle.cost, brute_force(&cycle_cost, goal));
+        assert_eq!(cycle.cost, cycle_cost(&cycle.order));
+        assert_eq!(cycle.order[0], 0);
+        assert!(cycle.order.iter().copied().sorted().eq(0..dist.len()));
+    }
+
+    assert_eq!(
+        best_path(&[vec![Some(0)]], Goal::Min).unwrap().order,
+        vec![0]
+    );
+
+    // 0 - 1 - 2 is the only way, and it can't go back to 0 from 2
+    let line = vec![
+        vec![None, Some(1), None],
+        vec![Some(1), None, Some(2)],
+        vec![None, Some(2), None],
+    ];
+    let path = best_path(&line, Goal::Max).unwrap();
+    assert_eq!(path.cost, 3);
+    assert!(path.order == [0, 1, 2] || path.order == [2, 1, 0]);
+    assert_eq!(best_cycle(&line, Goal::Min), None);
+    assert_eq!(best_cycle::<i32>(&[], Goal::Min), None);
+}
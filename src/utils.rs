@@ -1,13 +1,31 @@
 use core::fmt::Display;
 use std::cmp::max;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs;
+use std::hash::{Hash, Hasher};
+use std::iter::successors;
 use std::num::ParseIntError;
 use std::ops::{Deref, DerefMut, Index, IndexMut};
 use std::str::FromStr;
 
 use grid::Grid;
 
+mod bit_grid;
+pub mod flood_fill;
+mod grid_view;
+mod interval_set;
+mod parsing;
+mod segment;
+mod sparse_grid;
+
+pub use bit_grid::BitGrid;
+pub use grid_view::GridView;
+pub use interval_set::IntervalSet;
+pub use parsing::{fields, ints, key_values, parse_capture, parse_lines, sections};
+pub use segment::Segment;
+pub use sparse_grid::{Bounds, SparseGrid};
+
 pub fn load_file(filename: &str) -> String {
     fs::read_to_string("data/".to_string() + filename)
         .expect("Should have been able to read the file")
@@ -74,6 +92,57 @@ macro_rules! my_dbg {
     };
 }
 
+/// Declares a struct that implements `FromStr` with a regex. Each field gets the value of
+/// the named capture group with the same name, parsed with its own `FromStr`.
+///
+/// ```ignore
+/// regex_struct! {
+///     #[regex(r"move (?P<n>\d+) from (?P<from>\d+) to (?P<to>\d+)")]
+///     #[derive(Debug)]
+///     struct Move {
+///         n: usize,
+///         from: usize,
+///         to: usize,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! regex_struct {
+    (
+        #[regex($re:literal)]
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($field_vis $field: $ty),*
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = $crate::utils::ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                lazy_static::lazy_static! {
+                    static ref RE: regex::Regex = regex::Regex::new($re).unwrap();
+                }
+                let cap = RE.captures(s).ok_or_else(|| {
+                    $crate::utils::ParseError::new(&format!(
+                        "'{}' is not a {}, it doesn't match /{}/",
+                        s,
+                        stringify!($name),
+                        $re
+                    ))
+                })?;
+                Ok($name {
+                    $($field: $crate::utils::parse_capture(&cap, stringify!($field), s)?),*
+                })
+            }
+        }
+    };
+}
+
 #[derive(Debug)]
 pub struct ParseError {
     message: String,
@@ -93,6 +162,12 @@ impl ParseError {
     }
 }
 
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 impl From<ParseIntError> for ParseError {
     fn from(_source: ParseIntError) -> Self {
         ParseError::new(&_source.to_string())
@@ -114,6 +189,13 @@ pub enum Rotation {
 }
 
 impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
     pub fn rotate(&self, way: Rotation) -> Direction {
         match way {
             Rotation::Clockwise => match self {
@@ -132,6 +214,20 @@ impl Direction {
     }
 }
 
+impl Direction {
+    /// Direction to go from `from` to `to`, if they are on the same row or column
+    pub fn towards(from: &Point, to: &Point) -> Option<Direction> {
+        match (to.x - from.x, to.y - from.y) {
+            (0, 0) => None,
+            (0, dy) if dy < 0 => Some(Direction::Up),
+            (0, _) => Some(Direction::Down),
+            (dx, 0) if dx < 0 => Some(Direction::Left),
+            (_, 0) => Some(Direction::Right),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Direction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let repr = match self {
@@ -145,6 +241,21 @@ impl Display for Direction {
     }
 }
 
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    /// Either the initial (`U`, `D`, `L`, `R`) or the arrow
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" | "^" => Ok(Direction::Up),
+            "D" | "v" => Ok(Direction::Down),
+            "L" | "<" => Ok(Direction::Left),
+            "R" | ">" => Ok(Direction::Right),
+            _ => Err(ParseError::new(&format!("Unknown direction '{}'", s))),
+        }
+    }
+}
+
 impl Debug for Direction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let repr = match self {
@@ -208,6 +319,19 @@ impl From<(usize, usize)> for Point {
 impl Point {
     pub const ZERO: Self = Self { x: 0, y: 0 };
 
+    /// `(x, y)` offsets in the same order as [`Point::all_neighbors`], so the first 4 are the
+    /// ones of [`Point::neighbors`]
+    pub(crate) const ALL_OFFSETS: [(isize, isize); 8] = [
+        (0, -1),
+        (0, 1),
+        (-1, 0),
+        (1, 0),
+        (-1, -1),
+        (1, -1),
+        (-1, 1),
+        (1, 1),
+    ];
+
     pub fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
@@ -360,9 +484,24 @@ impl Debug for Point {
 /// Structure that represents a grid of stuff.
 ///
 /// The top left corner is `(0, 0)` and bottom right is `(width, height)`
+#[derive(Clone)]
 pub struct MyGrid<T>(pub Grid<T>);
 
-impl<T: Default> MyGrid<T> {
+impl<T: Eq> PartialEq for MyGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Eq> Eq for MyGrid<T> {}
+
+impl<T: Hash> Hash for MyGrid<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<T> MyGrid<T> {
     pub fn contains(&self, point: &Point) -> bool {
         point.x >= 0
             && point.y >= 0
@@ -370,6 +509,68 @@ impl<T: Default> MyGrid<T> {
             && (point.y as usize) < self.rows()
     }
 
+    /// Same as [`Point::neighbors`], but only the ones inside the grid
+    pub fn neighbors<'a>(&'a self, point: &Point) -> impl Iterator<Item = Point> + 'a {
+        self.neighbors_with_dir(point).map(|(p, _)| p)
+    }
+
+    /// Same as [`Point::all_neighbors`], but only the ones inside the grid
+    pub fn all_neighbors<'a>(&'a self, point: &Point) -> impl Iterator<Item = Point> + 'a {
+        let (x, y) = (point.x, point.y);
+        Point::ALL_OFFSETS
+            .into_iter()
+            .map(move |(dx, dy)| Point::new(x + dx, y + dy))
+            .filter(|p| self.contains(p))
+    }
+
+    /// The orthogonal neighbours inside the grid, together with the direction to get there
+    pub fn neighbors_with_dir<'a>(
+        &'a self,
+        point: &Point,
+    ) -> impl Iterator<Item = (Point, Direction)> + 'a {
+        let point = point.clone();
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| (point.move1(&dir), dir))
+            .filter(|(p, _)| self.contains(p))
+    }
+
+    pub fn neighbors_with_values<'a>(
+        &'a self,
+        point: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        self.neighbors(point).map(|p| {
+            let value = &self[&p];
+            (p, value)
+        })
+    }
+
+    pub fn all_neighbors_with_values<'a>(
+        &'a self,
+        point: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        self.all_neighbors(point).map(|p| {
+            let value = &self[&p];
+            (p, value)
+        })
+    }
+
+    /// Like [`MyGrid::to_edge`], but also yields the position of each value.
+    ///
+    /// The starting point is not included. Stops at the first point outside of the grid.
+    pub fn ray<'a>(
+        &'a self,
+        point: &Point,
+        dir: Direction,
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        successors(Some(point.move1(&dir)), move |p| Some(p.move1(&dir)))
+            .take_while(|p| self.contains(p))
+            .map(|p| {
+                let value = &self[&p];
+                (p, value)
+            })
+    }
+
     pub fn to_edge(&self, point: &Point, dir: Direction) -> Box<dyn Iterator<Item = &T> + '_> {
         let c = point.x as usize;
         let r = point.y as usize;
@@ -380,6 +581,94 @@ impl<T: Default> MyGrid<T> {
             Direction::Right => Box::new(self.iter_row(r).skip(c + 1)),
         }
     }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.0[(point.y as usize, point.x as usize)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.0[(point.y as usize, point.x as usize)])
+        } else {
+            None
+        }
+    }
+
+    /// Brings the point back inside the grid, as if the opposite edges were glued together.
+    /// An empty grid has nowhere to bring it, so the point stays as it is.
+    pub fn wrap(&self, point: &Point) -> Point {
+        Point {
+            x: point.x.checked_rem_euclid(self.cols() as isize).unwrap_or(point.x),
+            y: point.y.checked_rem_euclid(self.rows() as isize).unwrap_or(point.y),
+        }
+    }
+
+    /// View of the grid where going out of one edge comes back in by the opposite one
+    pub fn wrapping(&self) -> Wrapping<'_, T> {
+        Wrapping(self)
+    }
+}
+
+/// The grid seen as a torus. Every point is valid, and gets wrapped into the grid.
+///
+/// Created with [`MyGrid::wrapping`]
+pub struct Wrapping<'a, T>(&'a MyGrid<T>);
+
+impl<'a, T> Wrapping<'a, T> {
+    /// The 4 orthogonal neighbours, wrapped around the edges
+    pub fn neighbors(&self, point: &Point) -> impl Iterator<Item = Point> + 'a {
+        self.neighbors_with_dir(point).map(|(p, _)| p)
+    }
+
+    /// The 8 neighbours, wrapped around the edges
+    pub fn all_neighbors(&self, point: &Point) -> impl Iterator<Item = Point> + 'a {
+        let grid = self.0;
+        let (x, y) = (point.x, point.y);
+        Point::ALL_OFFSETS
+            .into_iter()
+            .map(move |(dx, dy)| grid.wrap(&Point::new(x + dx, y + dy)))
+    }
+
+    pub fn neighbors_with_dir(
+        &self,
+        point: &Point,
+    ) -> impl Iterator<Item = (Point, Direction)> + 'a {
+        let grid = self.0;
+        let point = point.clone();
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| (grid.wrap(&point.move1(&dir)), dir))
+    }
+
+    /// Goes around the grid once in `dir`, stopping right before getting back to `point`
+    pub fn ray(&self, point: &Point, dir: Direction) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        let grid = self.0;
+        let len = match dir {
+            Direction::Up | Direction::Down => grid.rows(),
+            Direction::Left | Direction::Right => grid.cols(),
+        };
+        successors(Some(grid.wrap(point)), move |p| {
+            Some(grid.wrap(&p.move1(&dir)))
+        })
+        .skip(1)
+        .take(len.saturating_sub(1))
+        .map(move |p| {
+            let value = &grid[&p];
+            (p, value)
+        })
+    }
+}
+
+impl<T> Index<&Point> for Wrapping<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: &Point) -> &Self::Output {
+        &self.0[&self.0.wrap(index)]
+    }
 }
 
 impl<T: Eq> MyGrid<T> {
@@ -404,6 +693,71 @@ impl<T: Default> MyGrid<T> {
         });
         MyGrid(grid)
     }
+
+    /// Like [`MyGrid::parse`], but the mapper returns `None` for characters that should not
+    /// be in the input, and the error says where they are
+    pub fn try_parse(input: &str, mapper: impl Fn(u8) -> Option<T>) -> Result<Self, ParseError> {
+        Self::try_parse_at(input, |_, c| mapper(c))
+    }
+
+    fn try_parse_at(
+        input: &str,
+        mut mapper: impl FnMut(Point, u8) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut grid = Grid::<T>::new(0, 0);
+        for (r, line) in input.lines().enumerate() {
+            let row: Result<Vec<T>, ParseError> = line
+                .trim_end()
+                .bytes()
+                .enumerate()
+                .map(|(c, x)| {
+                    let point = Point::from((r, c));
+                    mapper(point.clone(), x).ok_or_else(|| {
+                        ParseError::new(&format!(
+                            "Unexpected character '{}' at {}",
+                            x as char, point
+                        ))
+                    })
+                })
+                .collect();
+            grid.push_row(row?);
+        }
+        Ok(MyGrid(grid))
+    }
+}
+
+/// Positions of each of the markers found while parsing a grid
+pub type Markers = HashMap<char, Vec<Point>>;
+
+impl<T: Default + Clone> MyGrid<T> {
+    /// Like [`MyGrid::parse`], but each `(marker, value)` char found in the input is
+    /// replaced by `value` and its position saved
+    pub fn parse_with_markers(
+        input: &str,
+        mapper: fn(u8) -> T,
+        markers: &[(char, T)],
+    ) -> (Self, Markers) {
+        Self::try_parse_with_markers(input, |c| Some(mapper(c)), markers)
+            .expect("The mapper accepts all the characters")
+    }
+
+    pub fn try_parse_with_markers(
+        input: &str,
+        mapper: impl Fn(u8) -> Option<T>,
+        markers: &[(char, T)],
+    ) -> Result<(Self, Markers), ParseError> {
+        let mut positions = Markers::new();
+        let grid = Self::try_parse_at(input, |point, c| {
+            match markers.iter().find(|(marker, _)| *marker == c as char) {
+                Some((marker, value)) => {
+                    positions.entry(*marker).or_default().push(point);
+                    Some(value.clone())
+                }
+                None => mapper(c),
+            }
+        })?;
+        Ok((grid, positions))
+    }
 }
 
 impl MyGrid<char> {
@@ -448,27 +802,29 @@ impl<T> Index<&Point> for MyGrid<T> {
     type Output = T;
 
     fn index(&self, index: &Point) -> &Self::Output {
-        &self.0[(index.y as usize, index.x as usize)]
+        self.get(index)
+            .unwrap_or_else(|| panic!("{} is outside of the grid", index))
     }
 }
 
 impl<T> IndexMut<&Point> for MyGrid<T> {
     fn index_mut(&mut self, index: &Point) -> &mut Self::Output {
-        &mut self.0[(index.y as usize, index.x as usize)]
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("{} is outside of the grid", index))
     }
 }
 
 impl<T> Index<(isize, isize)> for MyGrid<T> {
     type Output = T;
 
-    fn index(&self, index: (isize, isize)) -> &Self::Output {
-        &self.0[(index.0 as usize, index.1 as usize)]
+    fn index(&self, (r, c): (isize, isize)) -> &Self::Output {
+        &self[&Point::new(c, r)]
     }
 }
 
 impl<T> IndexMut<(isize, isize)> for MyGrid<T> {
-    fn index_mut(&mut self, index: (isize, isize)) -> &mut Self::Output {
-        &mut self.0[(index.0 as usize, index.1 as usize)]
+    fn index_mut(&mut self, (r, c): (isize, isize)) -> &mut Self::Output {
+        &mut self[&Point::new(c, r)]
     }
 }
 
@@ -503,6 +859,29 @@ where
     }
 }
 
+impl<T: Display> MyGrid<T> {
+    /// Grid with the path drawn on top, to compare with the illustrations of the puzzles.
+    ///
+    /// Every cell the path goes through shows the arrow of the direction it was entered with.
+    /// Consecutive points must be on the same row or column, but don't need to be adjacent.
+    pub fn with_path(&self, path: &[Point]) -> MyGrid<String> {
+        let mut res = MyGrid(Grid::from_vec(
+            self.iter().map(|x| x.to_string()).collect(),
+            self.cols(),
+        ));
+        for (from, to) in path.iter().zip(path.iter().skip(1)) {
+            let dir = Direction::towards(from, to)
+                .unwrap_or_else(|| panic!("{} and {} are not in a straight line", from, to));
+            let mut pos = from.clone();
+            while pos != *to {
+                pos = pos.move1(&dir);
+                res[&pos] = dir.to_string();
+            }
+        }
+        res
+    }
+}
+
 impl<T> Display for MyGrid<T>
 where
     T: Display,
@@ -542,3 +921,149 @@ fn test_my_grid() {
     assert_eq!(grid.to_edge(&p(1, 3), Down).collect::<String>(), "|J.");
     assert_eq!(grid.to_edge(&p(1, 4), Left).collect::<String>(), "7-F.");
 }
+
+#[test]
+fn test_my_grid_neighbors() {
+    use indoc::indoc;
+    use itertools::Itertools;
+    use Direction::*;
+
+    fn p(r: usize, c: usize) -> Point {
+        Point::from((r, c))
+    }
+
+    let grid = MyGrid::cparse(indoc! {"
+        abc
+        def
+        ghi
+    "});
+
+    assert_eq!(
+        grid.neighbors(&p(1, 1)).collect_vec(),
+        vec![p(0, 1), p(2, 1), p(1, 0), p(1, 2)]
+    );
+    assert_eq!(
+        grid.neighbors(&p(0, 0)).collect_vec(),
+        vec![p(1, 0), p(0, 1)]
+    );
+    assert_eq!(grid.all_neighbors(&p(1, 1)).count(), 8);
+    assert_eq!(
+        grid.all_neighbors(&p(2, 2)).collect_vec(),
+        vec![p(1, 2), p(2, 1), p(1, 1)]
+    );
+    assert_eq!(
+        grid.neighbors_with_dir(&p(0, 2)).collect_vec(),
+        vec![(p(1, 2), Down), (p(0, 1), Left)]
+    );
+    assert_eq!(
+        grid.all_neighbors_with_values(&p(0, 0))
+            .map(|(_, c)| c)
+            .collect::<String>(),
+        "dbe"
+    );
+    assert_eq!(
+        grid.ray(&p(2, 0), Up).collect_vec(),
+        vec![(p(1, 0), &'d'), (p(0, 0), &'a')]
+    );
+    assert_eq!(grid.ray(&p(1, 2), Right).count(), 0);
+}
+
+#[test]
+fn test_my_grid_wrapping() {
+    use indoc::indoc;
+    use itertools::Itertools;
+    use Direction::*;
+
+    fn p(r: isize, c: isize) -> Point {
+        Point::from((r, c))
+    }
+
+    let grid = MyGrid::cparse(indoc! {"
+        abc
+        def
+    "});
+
+    assert_eq!(grid.get(&p(1, 2)), Some(&'f'));
+    assert_eq!(grid.get(&p(-1, 0)), None);
+    assert_eq!(grid.get(&p(0, 3)), None);
+    assert_eq!(grid[(1_isize, 2_isize)], 'f');
+    assert!(std::panic::catch_unwind(|| grid[(-1_isize, 0_isize)]).is_err());
+
+    let wrapping = grid.wrapping();
+    assert_eq!(wrapping[&p(-1, 0)], 'd');
+    assert_eq!(wrapping[&p(2, -1)], 'c');
+    assert_eq!(wrapping[&p(5, 7)], 'e');
+    assert_eq!(
+        wrapping.neighbors(&p(0, 0)).collect_vec(),
+        vec![p(1, 0), p(1, 0), p(0, 2), p(0, 1)]
+    );
+    assert_eq!(wrapping.all_neighbors(&p(0, 0)).count(), 8);
+    assert_eq!(
+        wrapping.ray(&p(1, 1), Right).collect_vec(),
+        vec![(p(1, 2), &'f'), (p(1, 0), &'d')]
+    );
+    assert_eq!(
+        wrapping
+            .ray(&p(0, 2), Left)
+            .map(|(_, c)| c)
+            .collect::<String>(),
+        "ba"
+    );
+    assert_eq!(
+        wrapping
+            .ray(&p(0, 0), Up)
+            .map(|(_, c)| c)
+            .collect::<String>(),
+        "d"
+    );
+
+    let empty = MyGrid::cparse("");
+    assert_eq!(empty.wrap(&p(-1, 2)), p(-1, 2));
+    assert_eq!(empty.wrapping().ray(&p(0, 0), Up).count(), 0);
+}
+
+#[test]
+fn test_my_grid_parse() {
+    use indoc::indoc;
+
+    let input = indoc! {"
+        Sab
+        cSE
+    "};
+    let (grid, markers) = MyGrid::try_parse_with_markers(
+        input,
+        |c| c.is_ascii_lowercase().then_some(c),
+        &[('S', b'a'), ('E', b'z')],
+    )
+    .unwrap();
+    assert_eq!(grid.iter().copied().collect::<Vec<_>>(), b"aabcaz");
+    assert_eq!(markers[&'S'], vec![Point::new(0, 0), Point::new(1, 1)]);
+    assert_eq!(markers[&'E'], vec![Point::new(2, 1)]);
+
+    let err = MyGrid::try_parse(input, |c| c.is_ascii_lowercase().then_some(c)).unwrap_err();
+    assert_eq!(err.message, "Unexpected character 'S' at (0, 0)");
+}
+
+#[test]
+fn test_my_grid_with_path() {
+    use indoc::indoc;
+
+    let grid = MyGrid::parse(
+        indoc! {"
+            123
+            456
+            789
+        "},
+        |x| x - b'0',
+    );
+    let path = [
+        Point::new(0, 0),
+        Point::new(2, 0),
+        Point::new(2, 2),
+        Point::new(1, 2),
+    ];
+    assert_eq!(
+        grid.with_path(&path).to_string(),
+        "\n  0 1>>\n  1 45v\n  2 7<v\n"
+    );
+}
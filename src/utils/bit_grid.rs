@@ -0,0 +1,270 @@
+use core::fmt::Display;
+
+use grid::Grid;
+
+use super::{MyGrid, Point};
+
+const WORD: usize = u64::BITS as usize;
+
+/// Grid of on/off cells, packed 64 per word so whole rows can be changed at once.
+///
+/// Every row starts in a new word, and the bits after the last column are always 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+/// Mask with the bits `from..=to` of word `i` of a row
+fn word_mask(i: usize, from: usize, to: usize) -> u64 {
+    let start = from.max(i * WORD) - i * WORD;
+    let end = to.min(i * WORD + WORD - 1) - i * WORD;
+    (u64::MAX >> (WORD - 1 - end)) & (u64::MAX << start)
+}
+
+impl BitGrid {
+    /// All the cells off
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words_per_row = cols.div_ceil(WORD);
+        BitGrid {
+            rows,
+            cols,
+            words_per_row,
+            bits: vec![0; rows * words_per_row],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (0..self.rows as isize).contains(&point.y) && (0..self.cols as isize).contains(&point.x)
+    }
+
+    fn row(&self, r: usize) -> &[u64] {
+        &self.bits[r * self.words_per_row..(r + 1) * self.words_per_row]
+    }
+
+    fn row_mut(&mut self, r: usize) -> &mut [u64] {
+        &mut self.bits[r * self.words_per_row..(r + 1) * self.words_per_row]
+    }
+
+    /// Off for the points outside of the grid
+    pub fn get(&self, point: &Point) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+        let (r, c) = (point.y as usize, point.x as usize);
+        self.row(r)[c / WORD] >> (c % WORD) & 1 == 1
+    }
+
+    pub fn set(&mut self, point: &Point, value: bool) {
+        self.set_rect(point, point, value)
+    }
+
+    pub fn toggle(&mut self, point: &Point) {
+        self.toggle_rect(point, point)
+    }
+
+    /// Applies `op(word, mask)` to every word that has some cell of the rectangle, where
+    /// `mask` has the bits of the cells that are inside it. Both corners are included.
+    fn update_rect(
+        &mut self,
+        top_left: &Point,
+        bottom_right: &Point,
+        op: impl Fn(u64, u64) -> u64,
+    ) {
+        assert!(
+            self.contains(top_left) && self.contains(bottom_right),
+            "{}..{} is outside of the grid",
+            top_left,
+            bottom_right
+        );
+        let (from, to) = (top_left.x as usize, bottom_right.x as usize);
+        for r in top_left.y as usize..=bottom_right.y as usize {
+            let row = self.row_mut(r);
+            for (i, word) in row
+                .iter_mut()
+                .enumerate()
+                .take(to / WORD + 1)
+                .skip(from / WORD)
+            {
+                *word = op(*word, word_mask(i, from, to));
+            }
+        }
+    }
+
+    pub fn set_rect(&mut self, top_left: &Point, bottom_right: &Point, value: bool) {
+        if value {
+            self.update_rect(top_left, bottom_right, |word, mask| word | mask)
+        } else {
+            self.update_rect(top_left, bottom_right, |word, mask| word & !mask)
+        }
+    }
+
+    pub fn toggle_rect(&mut self, top_left: &Point, bottom_right: &Point) {
+        self.update_rect(top_left, bottom_right, |word, mask| word ^ mask)
+    }
+
+    /// How many cells are on
+    pub fn count_ones(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Row `r` moved one column to the right, so each bit has the value of its left neighbour.
+    /// Out of the grid, everything is off.
+    fn with_left(&self, r: usize) -> Vec<u64> {
+        let row = self.row(r);
+        (0..row.len())
+            .map(|i| row[i] << 1 | if i > 0 { row[i - 1] >> (WORD - 1) } else { 0 })
+            .collect()
+    }
+
+    /// Same as [`BitGrid::with_left`], with the neighbour on the right
+    fn with_right(&self, r: usize) -> Vec<u64> {
+        let row = self.row(r);
+        (0..row.len())
+            .map(|i| row[i] >> 1 | row.get(i + 1).map_or(0, |next| next << (WORD - 1)))
+            .collect()
+    }
+
+    /// The 8 neighbours of every cell of row `r`, as one mask per direction
+    fn neighbor_masks(&self, r: usize) -> Vec<Vec<u64>> {
+        let mut res = vec![self.with_left(r), self.with_right(r)];
+        for other in [r.checked_sub(1), Some(r + 1).filter(|r| *r < self.rows)]
+            .into_iter()
+            .flatten()
+        {
+            res.push(self.row(other).to_vec());
+            res.push(self.with_left(other));
+            res.push(self.with_right(other));
+        }
+        res
+    }
+
+    /// Next generation of a game of life like automaton, with the 8 surrounding cells as
+    /// neighbours and everything outside of the grid off.
+    ///
+    /// `next(alive, alive_neighbors)` is only called once per combination, and then all the
+    /// cells are updated 64 at a time, counting the neighbours with bitwise additions.
+    pub fn step(&self, next: impl Fn(bool, usize) -> bool) -> Self {
+        let rules: Vec<(bool, bool)> = (0..=8).map(|n| (next(false, n), next(true, n))).collect();
+        let mut res = BitGrid::new(self.rows, self.cols);
+        for r in 0..self.rows {
+            let masks = self.neighbor_masks(r);
+            let current = self.row(r);
+            let row = res.row_mut(r);
+            for (i, word) in row.iter_mut().enumerate() {
+                // Binary digits of the neighbour count of each cell
+                let mut digits = [0_u64; 4];
+                for mask in &masks {
+                    let mut carry = mask[i];
+                    for digit in digits.iter_mut() {
+                        (*digit, carry) = (*digit ^ carry, *digit & carry);
+                    }
+                }
+                for (n, (born, survives)) in rules.iter().enumerate() {
+                    let count_is_n = digits
+                        .iter()
+                        .enumerate()
+                        .fold(u64::MAX, |acc, (bit, digit)| {
+                            acc & if n >> bit & 1 == 1 { *digit } else { !*digit }
+                        });
+                    if *born {
+                        *word |= count_is_n & !current[i];
+                    }
+                    if *survives {
+                        *word |= count_is_n & current[i];
+                    }
+                }
+            }
+            if let Some(last) = row.last_mut() {
+                *last &= word_mask(self.words_per_row - 1, 0, self.cols - 1);
+            }
+        }
+        res
+    }
+
+    pub fn to_grid(&self) -> MyGrid<bool> {
+        MyGrid(Grid::from_vec(
+            (0..self.rows * self.cols)
+                .map(|i| self.get(&Point::from((i / self.cols, i % self.cols))))
+                .collect(),
+            self.cols,
+        ))
+    }
+}
+
+impl From<&MyGrid<bool>> for BitGrid {
+    fn from(grid: &MyGrid<bool>) -> Self {
+        let mut res = BitGrid::new(grid.rows(), grid.cols());
+        for ((r, c), on) in grid.indexed_iter() {
+            if *on {
+                res.set(&Point::from((r, c)), true);
+            }
+        }
+        res
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f)?;
+        for r in 0..self.rows {
+            write!(f, "{:3} ", r)?;
+            for c in 0..self.cols {
+                let on = self.get(&Point::from((r, c)));
+                write!(f, "{}", if on { '#' } else { '.' })?
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_bit_grid() {
+    let mut grid = BitGrid::new(3, 150);
+    grid.set_rect(&Point::new(10, 0), &Point::new(139, 1), true);
+    assert_eq!(grid.count_ones(), 260);
+    grid.toggle_rect(&Point::new(0, 1), &Point::new(149, 2));
+    assert_eq!(grid.count_ones(), 130 + 20 + 150);
+    assert!(grid.get(&Point::new(64, 0)));
+    assert!(!grid.get(&Point::new(64, 1)));
+    assert!(grid.get(&Point::new(149, 1)));
+    assert!(!grid.get(&Point::new(150, 1)));
+    grid.set(&Point::new(149, 1), false);
+    grid.toggle(&Point::new(0, 0));
+    assert_eq!(grid.count_ones(), 300);
+    assert_eq!(BitGrid::from(&grid.to_grid()), grid);
+
+    // Same as the automaton, also across the words of a row
+    use crate::automaton::{Automaton, Boundary, LifeLike};
+    use itertools::Itertools;
+
+    let input = [".#.#.#", "...##.", "#....#", "..#...", "#.#..#", "####.."]
+        .iter()
+        .map(|line| line.repeat(13))
+        .join("\n");
+    let lights = MyGrid::parse(&input, |c| c == b'#');
+    let life = LifeLike::conway();
+    let rule = |x: &bool, ns: &[&bool]| life.next(*x, ns.iter().filter(|n| ***n).count());
+    let mut automaton = Automaton::new(lights.clone(), rule, Boundary::Fixed(false));
+    let mut grid = BitGrid::from(&lights);
+    for _ in 0..4 {
+        automaton.step();
+        grid = grid.step(|alive, n| life.next(alive, n));
+        assert_eq!(grid.to_grid(), automaton.grid);
+    }
+    assert_eq!(
+        BitGrid::from(&MyGrid::parse("#.\n##\n", |c| c == b'#')).to_string(),
+        "\n  0 #.\n  1 ##\n"
+    );
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use grid::Grid;
+
+use super::{Bounds, MyGrid, Point};
+
+/// Group of connected cells, see [`MyGrid::components`]
+#[derive(Debug)]
+pub struct Region {
+    pub label: usize,
+    pub points: Vec<Point>,
+    /// Number of cell sides that touch something outside the region (or the edge of the grid)
+    pub perimeter: usize,
+    pub bounds: Bounds,
+}
+
+impl Region {
+    pub fn size(&self) -> usize {
+        self.points.len()
+    }
+}
+
+/// All the regions of a grid, and the label of the region each cell belongs to
+#[derive(Debug)]
+pub struct Components {
+    pub labels: MyGrid<Option<usize>>,
+    pub regions: Vec<Region>,
+}
+
+impl<T> MyGrid<T> {
+    /// All the points that can be reached from `start` by going up, down, left or right,
+    /// without leaving the grid or going through a non passable cell.
+    ///
+    /// Empty if `start` itself is not passable.
+    pub fn flood_fill(
+        &self,
+        start: &Point,
+        passable: impl Fn(&Point, &T) -> bool,
+    ) -> HashSet<Point> {
+        let mut reached = HashSet::new();
+        if !self.get(start).is_some_and(|x| passable(start, x)) {
+            return reached;
+        }
+        let mut to_visit = vec![start.clone()];
+        reached.insert(start.clone());
+        while let Some(point) = to_visit.pop() {
+            for (next, x) in self.neighbors_with_values(&point) {
+                if passable(&next, x) && !reached.contains(&next) {
+                    reached.insert(next.clone());
+                    to_visit.push(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Splits the cells that satisfy `predicate` into connected regions
+    pub fn components(&self, predicate: impl Fn(&T) -> bool) -> Components {
+        let mut labels = MyGrid(Grid::init(self.rows(), self.cols(), None));
+        let mut regions = vec![];
+
+        for (idx, x) in self.indexed_iter() {
+            let start = Point::from(idx);
+            if labels[&start].is_some() || !predicate(x) {
+                continue;
+            }
+            let label = regions.len();
+            let mut region = Region {
+                label,
+                points: vec![],
+                perimeter: 0,
+                bounds: Bounds::new(&start),
+            };
+            labels[&start] = Some(label);
+            let mut to_visit = vec![start];
+            while let Some(point) = to_visit.pop() {
+                let mut inside_neighbors = 0;
+                for (next, x) in self.neighbors_with_values(&point) {
+                    if !predicate(x) {
+                        continue;
+                    }
+                    inside_neighbors += 1;
+                    if labels[&next].is_none() {
+                        labels[&next] = Some(label);
+                        to_visit.push(next);
+                    }
+                }
+                region.perimeter += 4 - inside_neighbors;
+                region.bounds.extend(&point);
+                region.points.push(point);
+            }
+            regions.push(region);
+        }
+        Components { labels, regions }
+    }
+}
+
+#[test]
+fn test_flood_fill() {
+    use indoc::indoc;
+
+    let grid = MyGrid::cparse(indoc! {"
+        ..#..
+        ..#.#
+        ###..
+        .#...
+    "});
+
+    let reached = grid.flood_fill(&Point::new(0, 0), |_, c| *c == '.');
+    assert_eq!(reached.len(), 4);
+    assert!(reached.contains(&Point::new(1, 1)));
+    assert_eq!(
+        grid.flood_fill(&Point::new(2, 0), |_, c| *c == '.').len(),
+        0
+    );
+    let reached = grid.flood_fill(&Point::new(4, 3), |p, c| *c == '.' && p.y > 1);
+    assert_eq!(reached.len(), 5);
+
+    let Components { labels, regions } = grid.components(|c| *c == '.');
+    let sizes: Vec<_> = regions.iter().map(|r| r.size()).collect();
+    assert_eq!(sizes, vec![4, 8, 1]);
+    assert_eq!(regions[0].perimeter, 8);
+    assert_eq!(regions[2].perimeter, 4);
+    assert_eq!(
+        regions[1].bounds,
+        Bounds {
+            top: 0,
+            bottom: 3,
+            left: 2,
+            right: 4,
+        }
+    );
+    assert_eq!(labels[&Point::new(3, 0)], Some(1));
+    assert_eq!(labels[&Point::new(0, 3)], Some(2));
+    assert_eq!(labels[&Point::new(2, 0)], None);
+}
@@ -0,0 +1,223 @@
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+
+use grid::Grid;
+
+use super::MyGrid;
+
+/// Rotated, transposed or flipped version of a [`MyGrid`], without copying anything.
+///
+/// Indexing the view translates the `(row, col)` into the position on the underlying grid,
+/// so an algorithm written for one direction can be reused for the others.
+/// `G` is either `&MyGrid<T>` or `&mut MyGrid<T>`, the later allowing changes through the view.
+pub struct GridView<G> {
+    grid: G,
+    transposed: bool,
+    flip_rows: bool,
+    flip_cols: bool,
+}
+
+impl<T> MyGrid<T> {
+    pub fn view(&self) -> GridView<&MyGrid<T>> {
+        GridView::new(self)
+    }
+
+    pub fn view_mut(&mut self) -> GridView<&mut MyGrid<T>> {
+        GridView::new(self)
+    }
+}
+
+impl<G, T> GridView<G>
+where
+    G: Deref<Target = MyGrid<T>>,
+{
+    fn new(grid: G) -> Self {
+        GridView {
+            grid,
+            transposed: false,
+            flip_rows: false,
+            flip_cols: false,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        if self.transposed {
+            self.grid.cols()
+        } else {
+            self.grid.rows()
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        if self.transposed {
+            self.grid.rows()
+        } else {
+            self.grid.cols()
+        }
+    }
+
+    /// Rows become columns and vice versa
+    pub fn transpose(self) -> Self {
+        GridView {
+            transposed: !self.transposed,
+            flip_rows: self.flip_cols,
+            flip_cols: self.flip_rows,
+            ..self
+        }
+    }
+
+    /// Upside down
+    pub fn flip_rows(self) -> Self {
+        GridView {
+            flip_rows: !self.flip_rows,
+            ..self
+        }
+    }
+
+    /// Mirrored left to right
+    pub fn flip_cols(self) -> Self {
+        GridView {
+            flip_cols: !self.flip_cols,
+            ..self
+        }
+    }
+
+    pub fn rotate_right(self) -> Self {
+        self.transpose().flip_cols()
+    }
+
+    pub fn rotate_left(self) -> Self {
+        self.transpose().flip_rows()
+    }
+
+    pub fn rotate_half(self) -> Self {
+        self.flip_rows().flip_cols()
+    }
+
+    /// Position in the underlying grid of the `(row, col)` of the view
+    pub fn source_index(&self, (row, col): (usize, usize)) -> (usize, usize) {
+        let row = if self.flip_rows {
+            self.rows() - 1 - row
+        } else {
+            row
+        };
+        let col = if self.flip_cols {
+            self.cols() - 1 - col
+        } else {
+            col
+        };
+        if self.transposed {
+            (col, row)
+        } else {
+            (row, col)
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows() && col < self.cols() {
+            Some(&self.grid[self.source_index((row, col))])
+        } else {
+            None
+        }
+    }
+
+    pub fn iter_row<'a>(&'a self, row: usize) -> impl DoubleEndedIterator<Item = &'a T> + 'a
+    where
+        T: 'a,
+    {
+        (0..self.cols()).map(move |col| &self[(row, col)])
+    }
+
+    pub fn iter_col<'a>(&'a self, col: usize) -> impl DoubleEndedIterator<Item = &'a T> + 'a
+    where
+        T: 'a,
+    {
+        (0..self.rows()).map(move |row| &self[(row, col)])
+    }
+
+    pub fn iter_rows<'a>(&'a self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &'a T>>
+    where
+        T: 'a,
+    {
+        (0..self.rows()).map(move |row| self.iter_row(row))
+    }
+
+    pub fn iter_cols<'a>(&'a self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &'a T>>
+    where
+        T: 'a,
+    {
+        (0..self.cols()).map(move |col| self.iter_col(col))
+    }
+}
+
+impl<G, T> GridView<G>
+where
+    G: Deref<Target = MyGrid<T>>,
+    T: Clone,
+{
+    /// Copies the view into a new grid
+    pub fn to_grid(&self) -> MyGrid<T> {
+        let cells = self.iter_rows().flatten().cloned().collect();
+        MyGrid(Grid::from_vec(cells, self.cols()))
+    }
+}
+
+impl<G, T> Index<(usize, usize)> for GridView<G>
+where
+    G: Deref<Target = MyGrid<T>>,
+{
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.grid[self.source_index(index)]
+    }
+}
+
+impl<G, T> IndexMut<(usize, usize)> for GridView<G>
+where
+    G: DerefMut<Target = MyGrid<T>>,
+{
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        let index = self.source_index(index);
+        &mut self.grid[index]
+    }
+}
+
+#[test]
+fn test_grid_view() {
+    use indoc::indoc;
+
+    let mut grid = MyGrid::cparse(indoc! {"
+        abc
+        def
+    "});
+
+    fn rows<G: Deref<Target = MyGrid<char>>>(view: &GridView<G>) -> Vec<String> {
+        view.iter_rows().map(|row| row.collect()).collect()
+    }
+
+    assert_eq!(rows(&grid.view()), vec!["abc", "def"]);
+    assert_eq!(rows(&grid.view().transpose()), vec!["ad", "be", "cf"]);
+    assert_eq!(rows(&grid.view().rotate_right()), vec!["da", "eb", "fc"]);
+    assert_eq!(rows(&grid.view().rotate_left()), vec!["cf", "be", "ad"]);
+    assert_eq!(rows(&grid.view().rotate_half()), vec!["fed", "cba"]);
+    assert_eq!(rows(&grid.view().flip_cols()), vec!["cba", "fed"]);
+    assert_eq!(
+        rows(&grid.view().rotate_right().rotate_right()),
+        vec!["fed", "cba"]
+    );
+    assert_eq!(
+        rows(&grid.view().rotate_left().rotate_right()),
+        vec!["abc", "def"]
+    );
+    assert_eq!(
+        grid.view().rotate_left().iter_col(1).collect::<String>(),
+        "fed"
+    );
+    assert_eq!(grid.view().transpose().get(2, 1), Some(&'f'));
+    assert_eq!(grid.view().transpose().get(1, 2), None);
+    assert_eq!(grid.view().rotate_right().to_grid().size(), (3, 2));
+
+    let mut view = grid.view_mut().rotate_right();
+    view[(0, 0)] = 'x';
+    assert_eq!(grid[(1_usize, 0_usize)], 'x');
+}
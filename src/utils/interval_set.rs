@@ -0,0 +1,209 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+use num::PrimInt;
+
+/// Set of integers, stored as a sorted list of disjoint ranges.
+///
+/// Any kind of range can be added (`a..b`, `a..=b`, `a..`, ...), but they are kept as
+/// inclusive ranges, so the whole domain of `T` can be represented without overflowing.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    /// Inclusive, sorted, not overlapping and not touching each other
+    ranges: Vec<(T, T)>,
+}
+
+/// `None` if the range is empty
+fn to_inclusive<T: PrimInt>(range: impl RangeBounds<T>) -> Option<(T, T)> {
+    let start = match range.start_bound() {
+        Bound::Included(x) => *x,
+        Bound::Excluded(x) => x.checked_add(&T::one())?,
+        Bound::Unbounded => T::min_value(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(x) => *x,
+        Bound::Excluded(x) => x.checked_sub(&T::one())?,
+        Bound::Unbounded => T::max_value(),
+    };
+    (start <= end).then_some((start, end))
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    pub fn from_range(range: impl RangeBounds<T>) -> Self {
+        IntervalSet {
+            ranges: to_inclusive(range).into_iter().collect(),
+        }
+    }
+
+    /// Sorts and merges the ranges that overlap or touch
+    fn normalized(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.sort();
+        let mut res: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match res.last_mut() {
+                Some(last) if last.1 == T::max_value() || start <= last.1 + T::one() => {
+                    last.1 = last.1.max(end)
+                }
+                _ => res.push((start, end)),
+            }
+        }
+        IntervalSet { ranges: res }
+    }
+
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        if let Some(range) = to_inclusive(range) {
+            let mut ranges = std::mem::take(&mut self.ranges);
+            ranges.push(range);
+            *self = Self::normalized(ranges);
+        }
+    }
+
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        *self = self.difference(&Self::from_range(range));
+    }
+
+    pub fn contains(&self, val: T) -> bool {
+        // Index of the first range that starts after `val`
+        let idx = self.ranges.partition_point(|(start, _)| *start <= val);
+        idx > 0 && val <= self.ranges[idx - 1].1
+    }
+
+    /// The range that contains `val`, if any
+    pub fn range_of(&self, val: T) -> Option<RangeInclusive<T>> {
+        let idx = self.ranges.partition_point(|(start, _)| *start <= val);
+        let (start, end) = *self.ranges.get(idx.checked_sub(1)?)?;
+        (val <= end).then_some(start..=end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many numbers are in the set. Overflows if the set is the whole domain of `T`
+    pub fn len(&self) -> T {
+        self.ranges.iter().fold(T::zero(), |acc, (start, end)| {
+            acc + (*end - *start) + T::one()
+        })
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|(start, _)| *start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|(_, end)| *end)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized(self.ranges.iter().chain(&other.ranges).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut res = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                res.push((start, end));
+            }
+            // The one that finishes first can't intersect anything else
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges: res }
+    }
+
+    /// Every number of `T` that is not in the set
+    pub fn complement(&self) -> Self {
+        let mut res = vec![];
+        let mut next_start = Some(T::min_value());
+        for (start, end) in &self.ranges {
+            if let Some(next) = next_start {
+                if next < *start {
+                    res.push((next, *start - T::one()));
+                }
+            }
+            next_start = end.checked_add(&T::one());
+        }
+        if let Some(next) = next_start {
+            res.push((next, T::max_value()));
+        }
+        IntervalSet { ranges: res }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement())
+    }
+
+    /// Splits the set into the numbers smaller than `val`, and the ones bigger or equal
+    pub fn split_at(&self, val: T) -> (Self, Self) {
+        (
+            self.intersection(&Self::from_range(..val)),
+            self.intersection(&Self::from_range(val..)),
+        )
+    }
+}
+
+impl<T: PrimInt, R: RangeBounds<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().filter_map(to_inclusive).collect())
+    }
+}
+
+impl<T: Debug> Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.ranges.iter().map(|(start, end)| start..=end))
+            .finish()
+    }
+}
+
+#[test]
+fn test_interval_set() {
+    let set: IntervalSet<i32> = [1..=3, 10..=20, 4..=5].into_iter().collect();
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=5, 10..=20]);
+    assert_eq!(set.len(), 16);
+    assert!(set.contains(5));
+    assert!(!set.contains(6));
+    assert!(!set.contains(0));
+    assert_eq!(set.range_of(12), Some(10..=20));
+    assert_eq!(set.range_of(7), None);
+
+    let other = IntervalSet::from_range(3..12);
+    assert_eq!(
+        set.intersection(&other),
+        [3..=5, 10..=11].into_iter().collect()
+    );
+    assert_eq!(set.union(&other), IntervalSet::from_range(1..=20));
+    assert_eq!(
+        set.difference(&other),
+        [1..=2, 12..=20].into_iter().collect()
+    );
+
+    let (below, above) = set.split_at(11);
+    assert_eq!(below, [1..=5, 10..=10].into_iter().collect());
+    assert_eq!(above, IntervalSet::from_range(11..=20));
+
+    let mut set = IntervalSet::from_range(0_u8..);
+    assert_eq!(set.complement(), IntervalSet::new());
+    set.remove(10..=250);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..=9, 251..=255]);
+    assert_eq!(set.complement(), IntervalSet::from_range(10..251));
+    set.insert(10..11);
+    assert_eq!(set.len(), 16);
+    assert_eq!(IntervalSet::from_range(5..5).len(), 0);
+}
@@ -0,0 +1,292 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use regex::Captures;
+
+use super::ParseError;
+
+/// Column (starting at 1) where `part` starts, if it is a slice of `line`
+fn column(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Parses `part`, a slice of `line`, saying where it is if it fails
+fn parse_at<T>(line: &str, part: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    part.parse().map_err(|e| {
+        ParseError::new(&format!(
+            "Invalid value '{}' at column {} of '{}': {}",
+            part,
+            column(line, part),
+            line,
+            e
+        ))
+    })
+}
+
+/// All the integers in `line`, ignoring everything else. A `-` just before the digits
+/// makes the number negative, unless it comes right after another number, like in `2-4`.
+pub fn ints<T>(line: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = line.as_bytes();
+    let mut res = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = if i > 0 && bytes[i - 1] == b'-' && (i < 2 || !bytes[i - 2].is_ascii_digit()) {
+            i - 1
+        } else {
+            i
+        };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        res.push(parse_at(line, &line[start..i])?);
+    }
+    Ok(res)
+}
+
+/// Blocks of lines separated by empty lines
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|section| section.trim_matches('\n'))
+        .filter(|section| !section.is_empty())
+}
+
+/// Same as `input.lines().map(parser)`, but the errors say in which line they happened
+pub fn parse_lines<T>(
+    input: &str,
+    parser: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            parser(line).map_err(|e| ParseError::new(&format!("Line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Value of the capture group `name` of a match in `input`, see [`regex_struct`](crate::regex_struct)
+pub fn parse_capture<T>(cap: &Captures, name: &str, input: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let group = cap
+        .name(name)
+        .ok_or_else(|| ParseError::new(&format!("There is no '{}' in '{}'", name, input)))?;
+    group.as_str().parse().map_err(|e| {
+        ParseError::new(&format!(
+            "Invalid {} '{}' at column {} of '{}': {}",
+            name,
+            group.as_str(),
+            group.start() + 1,
+            input,
+            e
+        ))
+    })
+}
+
+/// Values taken from a line with [`fields`]
+#[derive(Debug)]
+pub struct Fields<'a> {
+    line: &'a str,
+    values: Vec<&'a str>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> &'a str {
+        self.values[idx]
+    }
+
+    pub fn parse<T>(&self, idx: usize) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        parse_at(self.line, self.values[idx])
+    }
+}
+
+/// Takes the values that are in place of the `{}` of `template`, the rest of the line must be
+/// exactly like the template. Each value goes until the first place where the text after
+/// its `{}` appears.
+pub fn fields<'a>(line: &'a str, template: &str) -> Result<Fields<'a>, ParseError> {
+    let mut parts = template.split("{}");
+    let prefix = parts.next().unwrap_or_default();
+    let expected = |rest: &str, text: &str| {
+        ParseError::new(&format!(
+            "Expected '{}' at column {} of '{}'",
+            text,
+            column(line, rest),
+            line
+        ))
+    };
+    let mut rest = line
+        .strip_prefix(prefix)
+        .ok_or_else(|| expected(line, prefix))?;
+    let mut values = vec![];
+    for text in parts {
+        let end = match text {
+            "" => rest.len(),
+            _ => rest.find(text).ok_or_else(|| expected(rest, text))?,
+        };
+        values.push(&rest[..end]);
+        rest = &rest[end + text.len()..];
+    }
+    match rest {
+        "" => Ok(Fields { line, values }),
+        _ => Err(ParseError::new(&format!(
+            "Unexpected '{}' at column {} of '{}'",
+            rest,
+            column(line, rest),
+            line
+        ))),
+    }
+}
+
+/// Pairs of a list like `a: 1, b: 2`, with `separator` between the key and the value, and
+/// `delimiter` between the pairs. Spaces around them are ignored.
+pub fn key_values<'a>(
+    line: &'a str,
+    separator: &str,
+    delimiter: &str,
+) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    // Only the spaces around them are optional, unless they are just spaces
+    fn trimmed(s: &str) -> &str {
+        match s.trim() {
+            "" => s,
+            t => t,
+        }
+    }
+    let (separator, delimiter) = (trimmed(separator), trimmed(delimiter));
+    line.split(delimiter)
+        .map(|pair| {
+            let (key, value) = pair.split_once(separator).ok_or_else(|| {
+                ParseError::new(&format!(
+                    "Expected '{}' after column {} of '{}'",
+                    separator,
+                    column(line, pair),
+                    line
+                ))
+            })?;
+            Ok((key.trim(), value.trim()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_parsing() {
+    use indoc::indoc;
+
+    assert_eq!(ints::<i32>("x=-3, y=12..15").unwrap(), vec![-3, 12, 15]);
+    assert_eq!(
+        ints::<u8>("a-1 b").unwrap_err().to_string(),
+        "Invalid value '-1' at column 2 of 'a-1 b': invalid digit found in string"
+    );
+    assert!(ints::<u8>("1000").is_err());
+    assert_eq!(ints::<i32>("2-4,6-8").unwrap(), vec![2, 4, 6, 8]);
+    assert_eq!(ints::<i32>("-2--4").unwrap(), vec![-2, -4]);
+
+    let input = indoc! {"
+        a
+        b
+
+        c
+
+
+        d
+    "};
+    assert_eq!(sections(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+
+    let line = "Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.";
+    let template = "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.";
+    let f = fields(line, template).unwrap();
+    assert_eq!(f.len(), 4);
+    assert_eq!(f.get(0), "Comet");
+    assert_eq!(f.parse::<u32>(3).unwrap(), 127);
+    assert_eq!(fields("a=1,b=2", "a={},b={}").unwrap().get(1), "2");
+    assert_eq!(
+        f.parse::<u32>(0).unwrap_err().to_string(),
+        format!(
+            "Invalid value 'Comet' at column 1 of '{}': invalid digit found in string",
+            line
+        )
+    );
+    assert_eq!(
+        fields("Comet can run 14", "{} can fly {}")
+            .unwrap_err()
+            .to_string(),
+        "Expected ' can fly ' at column 1 of 'Comet can run 14'"
+    );
+    assert_eq!(fields("x 1 y", "x {}").unwrap().get(0), "1 y");
+    assert_eq!(
+        fields("x 1 y", "x {} ").unwrap_err().to_string(),
+        "Unexpected 'y' at column 5 of 'x 1 y'"
+    );
+
+    assert_eq!(
+        key_values("children: 3, cats: 7", ": ", ", ").unwrap(),
+        vec![("children", "3"), ("cats", "7")]
+    );
+    assert!(key_values("children: 3, cats 7", ": ", ", ").is_err());
+    assert_eq!(
+        key_values("children:3 ,cats :  7,  trees: 1", ": ", ", ").unwrap(),
+        vec![("children", "3"), ("cats", "7"), ("trees", "1")]
+    );
+    assert_eq!(
+        key_values("a=1 b=2", "=", " ").unwrap(),
+        vec![("a", "1"), ("b", "2")]
+    );
+
+    let res = parse_lines("1 2\n3 x", |line| fields(line, "{} {}")?.parse::<u8>(1));
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "Line 2: Invalid value 'x' at column 3 of '3 x': invalid digit found in string"
+    );
+}
+
+#[test]
+fn test_regex_struct() {
+    use super::Direction;
+
+    crate::regex_struct! {
+        #[regex(r"(?P<dir>[UDLR]) (?P<steps>\d+)")]
+        #[derive(Debug)]
+        struct Move {
+            dir: Direction,
+            steps: u8,
+        }
+    }
+
+    let m: Move = "R 12".parse().unwrap();
+    assert_eq!(m.dir, Direction::Right);
+    assert_eq!(m.steps, 12);
+    assert_eq!(
+        "U 300".parse::<Move>().unwrap_err().to_string(),
+        "Invalid steps '300' at column 3 of 'U 300': number too large to fit in target type"
+    );
+    assert_eq!(
+        "X 3".parse::<Move>().unwrap_err().to_string(),
+        r"'X 3' is not a Move, it doesn't match /(?P<dir>[UDLR]) (?P<steps>\d+)/"
+    );
+}
@@ -0,0 +1,205 @@
+use num::integer::gcd;
+
+use super::Point;
+
+/// Straight line between two points, both of them included
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+fn cross(a: (isize, isize), b: (isize, isize)) -> isize {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn dot(a: (isize, isize), b: (isize, isize)) -> isize {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn diff(a: &Point, b: &Point) -> (isize, isize) {
+    (a.x - b.x, a.y - b.y)
+}
+
+impl Segment {
+    pub fn new(start: Point, end: Point) -> Self {
+        Segment { start, end }
+    }
+
+    fn delta(&self) -> (isize, isize) {
+        diff(&self.end, &self.start)
+    }
+
+    pub fn is_point(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    /// At 45°
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.delta();
+        dx.abs() == dy.abs()
+    }
+
+    /// The cells the segment goes through, from `start` to `end`.
+    ///
+    /// Exact for horizontal, vertical and 45° segments, and Bresenham's approximation
+    /// for any other slope.
+    pub fn cells(&self) -> impl Iterator<Item = Point> {
+        let (dx, dy) = (self.delta().0.abs(), -self.delta().1.abs());
+        let (sx, sy) = (self.delta().0.signum(), self.delta().1.signum());
+        let end = self.end.clone();
+        let mut err = dx + dy;
+        let mut next = Some(self.start.clone());
+        std::iter::from_fn(move || {
+            let curr = next.take()?;
+            if curr != end {
+                let mut p = curr.clone();
+                let e2 = 2 * err;
+                if e2 >= dy {
+                    err += dy;
+                    p.x += sx;
+                }
+                if e2 <= dx {
+                    err += dx;
+                    p.y += sy;
+                }
+                next = Some(p);
+            }
+            Some(curr)
+        })
+    }
+
+    /// Whether `point` is exactly on the segment
+    pub fn contains(&self, point: &Point) -> bool {
+        let r = self.delta();
+        let p = diff(point, &self.start);
+        cross(p, r) == 0 && (0..=dot(r, r)).contains(&dot(p, r)) && (r != (0, 0) || p == (0, 0))
+    }
+
+    /// The integer points that are on both segments, as a (possibly single point) segment
+    /// that goes in the same direction as `self`.
+    ///
+    /// `None` if they don't touch, or if they only cross between integer coordinates.
+    pub fn intersection(&self, other: &Segment) -> Option<Segment> {
+        if other.is_point() {
+            return self.contains(&other.start).then(|| other.clone());
+        }
+        if self.is_point() {
+            return other.contains(&self.start).then(|| self.clone());
+        }
+        let r = self.delta();
+        let s = other.delta();
+        let ca = diff(&other.start, &self.start);
+        let mut denom = cross(r, s);
+        if denom == 0 {
+            return if cross(ca, r) == 0 {
+                self.collinear_overlap(other)
+            } else {
+                None
+            };
+        }
+        // self.start + r * t_num / denom == other.start + s * u_num / denom
+        let (mut t_num, mut u_num) = (cross(ca, s), cross(ca, r));
+        if denom < 0 {
+            (denom, t_num, u_num) = (-denom, -t_num, -u_num);
+        }
+        if !(0..=denom).contains(&t_num) || !(0..=denom).contains(&u_num) {
+            return None;
+        }
+        if (t_num * r.0) % denom != 0 || (t_num * r.1) % denom != 0 {
+            return None;
+        }
+        let point = Point::new(
+            self.start.x + t_num * r.0 / denom,
+            self.start.y + t_num * r.1 / denom,
+        );
+        Some(Segment::new(point.clone(), point))
+    }
+
+    /// Both segments are on the same line, and none of them is a single point
+    fn collinear_overlap(&self, other: &Segment) -> Option<Segment> {
+        let r = self.delta();
+        let steps = gcd(r.0, r.1);
+        // Smallest integer step along the line, so every point of both segments is a
+        // whole number of steps away from `self.start`
+        let step = (r.0 / steps, r.1 / steps);
+        let pos = |p: &Point| dot(diff(p, &self.start), step) / dot(step, step);
+        let (a, b) = (pos(&other.start), pos(&other.end));
+        let from = a.min(b).max(0);
+        let to = a.max(b).min(steps);
+        let at = |k: isize| Point::new(self.start.x + k * step.0, self.start.y + k * step.1);
+        (from <= to).then(|| Segment::new(at(from), at(to)))
+    }
+}
+
+#[test]
+fn test_segment() {
+    use itertools::Itertools;
+
+    fn seg(x1: isize, y1: isize, x2: isize, y2: isize) -> Segment {
+        Segment::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+    fn cells(s: Segment) -> Vec<(isize, isize)> {
+        s.cells().map(|p| (p.x, p.y)).collect_vec()
+    }
+
+    assert_eq!(cells(seg(2, 1, 0, 1)), vec![(2, 1), (1, 1), (0, 1)]);
+    assert_eq!(cells(seg(0, 0, 0, 2)), vec![(0, 0), (0, 1), (0, 2)]);
+    assert_eq!(cells(seg(3, 0, 1, 2)), vec![(3, 0), (2, 1), (1, 2)]);
+    assert_eq!(cells(seg(1, 1, 1, 1)), vec![(1, 1)]);
+    assert_eq!(
+        cells(seg(0, 0, 4, 2)),
+        vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+    );
+    assert!(seg(3, 0, 1, 2).is_diagonal());
+    assert!(seg(0, 0, 4, 2).contains(&Point::new(2, 1)));
+    assert!(!seg(0, 0, 4, 2).contains(&Point::new(3, 1)));
+    assert!(!seg(0, 0, 4, 2).contains(&Point::new(6, 3)));
+
+    // Crossing
+    assert_eq!(
+        seg(0, 2, 4, 2).intersection(&seg(1, 0, 1, 5)),
+        Some(seg(1, 2, 1, 2))
+    );
+    assert_eq!(
+        seg(0, 0, 4, 4).intersection(&seg(0, 4, 4, 0)),
+        Some(seg(2, 2, 2, 2))
+    );
+    // Crossing between integer coordinates
+    assert_eq!(seg(0, 0, 1, 1).intersection(&seg(0, 1, 1, 0)), None);
+    // Touching at the end
+    assert_eq!(
+        seg(0, 0, 0, 3).intersection(&seg(0, 3, 5, 3)),
+        Some(seg(0, 3, 0, 3))
+    );
+    assert_eq!(seg(0, 0, 0, 3).intersection(&seg(1, 3, 5, 3)), None);
+    // Parallel
+    assert_eq!(seg(0, 0, 3, 0).intersection(&seg(0, 1, 3, 1)), None);
+    // Overlapping
+    assert_eq!(
+        seg(0, 0, 6, 0).intersection(&seg(8, 0, 4, 0)),
+        Some(seg(4, 0, 6, 0))
+    );
+    assert_eq!(
+        seg(6, 3, 0, 0).intersection(&seg(-2, -1, 2, 1)),
+        Some(seg(2, 1, 0, 0))
+    );
+    assert_eq!(seg(0, 0, 2, 0).intersection(&seg(3, 0, 5, 0)), None);
+    // Single points
+    assert_eq!(
+        seg(0, 0, 4, 4).intersection(&seg(3, 3, 3, 3)),
+        Some(seg(3, 3, 3, 3))
+    );
+    assert_eq!(
+        seg(3, 3, 3, 3).intersection(&seg(0, 0, 4, 4)),
+        Some(seg(3, 3, 3, 3))
+    );
+}
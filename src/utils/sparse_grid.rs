@@ -0,0 +1,214 @@
+use core::fmt::Display;
+use std::collections::HashMap;
+use std::ops::Index;
+
+use grid::Grid;
+
+use super::{MyGrid, Point};
+
+/// Smallest rectangle containing a set of points. All the limits are inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bounds {
+    pub top: isize,
+    pub bottom: isize,
+    pub left: isize,
+    pub right: isize,
+}
+
+impl Bounds {
+    pub fn new(point: &Point) -> Self {
+        Bounds {
+            top: point.y,
+            bottom: point.y,
+            left: point.x,
+            right: point.x,
+        }
+    }
+
+    pub fn extend(&mut self, point: &Point) {
+        self.top = self.top.min(point.y);
+        self.bottom = self.bottom.max(point.y);
+        self.left = self.left.min(point.x);
+        self.right = self.right.max(point.x);
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.top..=self.bottom).contains(&point.y) && (self.left..=self.right).contains(&point.x)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.right - self.left + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.bottom - self.top + 1) as usize
+    }
+
+    pub fn top_left(&self) -> Point {
+        Point::new(self.left, self.top)
+    }
+}
+
+/// Grid without a fixed size. Only the cells that were set are stored, every other
+/// position has the default value.
+///
+/// Keeps track of the bounding box of all the set cells, so it can be printed or
+/// converted to a [`MyGrid`]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+    bounds: Option<Bounds>,
+}
+
+impl<T: Default> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::with_default(T::default())
+    }
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn with_default(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, point: &Point) -> &T {
+        self.cells.get(point).unwrap_or(&self.default)
+    }
+
+    /// Returns the previous value if that position was already set
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.extend(&point),
+            None => self.bounds = Some(Bounds::new(&point)),
+        }
+        self.cells.insert(point, value)
+    }
+
+    /// Is this position explicitly set
+    pub fn is_set(&self, point: &Point) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    /// Number of positions that were set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// `None` if nothing was set yet
+    pub fn bounds(&self) -> Option<&Bounds> {
+        self.bounds.as_ref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Inserts the default value if the position was not set yet
+    pub fn get_mut(&mut self, point: &Point) -> &mut T {
+        if !self.cells.contains_key(point) {
+            self.insert(point.clone(), self.default.clone());
+        }
+        self.cells.get_mut(point).unwrap()
+    }
+
+    /// Dense version of the occupied region. The `(0, 0)` of the new grid corresponds
+    /// to the top left corner of [`SparseGrid::bounds`]
+    pub fn to_grid(&self) -> MyGrid<T> {
+        let Some(bounds) = &self.bounds else {
+            return MyGrid(Grid::init(0, 0, self.default.clone()));
+        };
+        let mut grid = MyGrid(Grid::init(
+            bounds.height(),
+            bounds.width(),
+            self.default.clone(),
+        ));
+        for (p, v) in &self.cells {
+            grid[(p.y - bounds.top, p.x - bounds.left)] = v.clone();
+        }
+        grid
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T>
+where
+    T: Default,
+{
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        iter.into_iter().for_each(|(p, v)| {
+            grid.insert(p, v);
+        });
+        grid
+    }
+}
+
+impl<T> Index<&Point> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: &Point) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+impl<T> Display for SparseGrid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f)?;
+        let Some(bounds) = &self.bounds else {
+            return Ok(());
+        };
+        for r in bounds.top..=bounds.bottom {
+            write!(f, "{:3} ", r)?;
+            for c in bounds.left..=bounds.right {
+                write!(f, "{}", self.get(&Point::new(c, r)))?
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sparse_grid() {
+    let mut grid = SparseGrid::with_default('.');
+    assert_eq!(grid.bounds(), None);
+    grid.insert(Point::new(-1, 2), '#');
+    grid.insert(Point::new(2, 0), '#');
+    *grid.get_mut(&Point::new(0, 1)) = 'x';
+
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid[&Point::new(5, 5)], '.');
+    assert_eq!(grid[&Point::new(0, 1)], 'x');
+    assert_eq!(
+        grid.bounds(),
+        Some(&Bounds {
+            top: 0,
+            bottom: 2,
+            left: -1,
+            right: 2
+        })
+    );
+    assert_eq!(grid.to_string(), "\n  0 ...#\n  1 .x..\n  2 #...\n");
+
+    let dense = grid.to_grid();
+    assert_eq!(dense.size(), (3, 4));
+    assert_eq!(dense[(2_usize, 0_usize)], '#');
+}
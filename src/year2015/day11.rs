@@ -9,7 +9,7 @@ fn is_valid_pass(pass: &[u8]) -> bool {
     increasing && no_iol && pairs
 }
 
-fn next_pass(pass: &mut Vec<u8>) {
+fn next_pass(pass: &mut [u8]) {
     let mut pos = (pass.len() - 1) as isize;
     while pos >= 0 {
         let c = pass[pos as usize];
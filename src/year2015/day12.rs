@@ -1,31 +1,51 @@
-use regex::Regex;
-use serde_json::Value;
+use crate::combinators::{
+    delimited, literal, map, number, or, pair, parse_all, quoted, separated, ws, PResult,
+};
 
-pub fn find_nums(val: &Value) -> i64 {
+#[derive(Debug)]
+enum Json<'a> {
+    Number(i64),
+    Str(&'a str),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(&'a str, Json<'a>)>),
+}
+
+fn json(input: &str) -> PResult<'_, Json<'_>> {
+    let field = pair(ws(quoted()), map(pair(literal(":"), json), |(_, v)| v));
+    ws(or(
+        or(map(number(), Json::Number), map(quoted(), Json::Str)),
+        or(
+            map(delimited("[", separated(json, ","), "]"), Json::Array),
+            map(delimited("{", separated(field, ","), "}"), Json::Object),
+        ),
+    ))(input)
+}
+
+fn sum_numbers(val: &Json, skip_red: bool) -> i64 {
     match val {
-        Value::Null => 0,
-        Value::Bool(_) => 0,
-        Value::Number(x) => x.as_i64().unwrap(),
-        Value::String(_) => 0,
-        Value::Array(a) => a.iter().map(find_nums).sum(),
-        Value::Object(obj) => {
-            if obj.iter().any(|(_, v)| v.as_str() == Some("red")) {
+        Json::Number(x) => *x,
+        Json::Str(_) => 0,
+        Json::Array(a) => a.iter().map(|v| sum_numbers(v, skip_red)).sum(),
+        Json::Object(obj) => {
+            if skip_red && obj.iter().any(|(_, v)| matches!(v, Json::Str("red"))) {
                 0
             } else {
-                obj.iter().map(|(_, v)| find_nums(v)).sum()
+                obj.iter().map(|(_, v)| sum_numbers(v, skip_red)).sum()
             }
-        },
+        }
     }
 }
 
-pub fn part1(input: &str) -> i32 {
-    let re = Regex::new(r"-?\d+").unwrap();
-    re.find_iter(input).map(|m| m.as_str().parse::<i32>().unwrap()).sum()
+fn parse(input: &str) -> Json<'_> {
+    parse_all(json, input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn part1(input: &str) -> i64 {
+    sum_numbers(&parse(input), false)
 }
 
 pub fn part2(input: &str) -> i64 {
-    let v: Value = serde_json::from_str(input).unwrap();
-    find_nums(&v)
+    sum_numbers(&parse(input), true)
 }
 
 #[test]
@@ -36,9 +56,9 @@ fn test() {
     assert_eq!(part1(r#"{"a":{"b":4},"c":-1}"#), 3);
     assert_eq!(part1(r#"{"a":[-1,1]}"#), 0);
     assert_eq!(part1(r#"[-1,{"a":1}]"#), 0);
-    
+
     assert_eq!(part2("[1,2,3]"), 6);
     assert_eq!(part2(r#"[1,{"c":"red","b":2},3]"#), 4);
     assert_eq!(part2(r#"{"d":"red","e":[1,2,3,4],"f":5}"#), 0);
     assert_eq!(part2(r#"[1,"red",5]"#), 6);
-}
\ No newline at end of file
+}
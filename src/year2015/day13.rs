@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 
-use itertools::Itertools;
+use crate::tsp::{best_cycle, Goal};
+use crate::utils::fields;
 
 type Nodes<'a> = HashMap<&'a str, u8>;
 type Edges = HashMap<(u8, u8), i32>;
 
-fn parse(input: &str) -> (Nodes, Edges) {
+fn parse(input: &str) -> (Nodes<'_>, Edges) {
     let mut nodes: Nodes = HashMap::new();
     let mut edges: Edges = HashMap::new();
     let mut curr = 0;
@@ -18,12 +19,12 @@ fn parse(input: &str) -> (Nodes, Edges) {
             }
         });
     input.lines().for_each(|line| {
-        let seq = line.strip_suffix('.').unwrap().split_whitespace().collect::<Vec<_>>();
-        let edge1 = *nodes.get(seq[0]).unwrap();
-        let edge2 = *nodes.get(seq[10]).unwrap();
-        let cost = match seq[2] {
-            "gain" => seq[3].parse::<i32>().unwrap(),
-            "lose" => -seq[3].parse::<i32>().unwrap(),
+        let f = fields(line, "{} would {} {} happiness units by sitting next to {}.").unwrap();
+        let edge1 = *nodes.get(f.get(0)).unwrap();
+        let edge2 = *nodes.get(f.get(3)).unwrap();
+        let cost = match f.get(1) {
+            "gain" => f.parse::<i32>(2).unwrap(),
+            "lose" => -f.parse::<i32>(2).unwrap(),
             _ => panic!("wtf")
         };
         edges.insert((edge1, edge2), cost);
@@ -31,39 +32,46 @@ fn parse(input: &str) -> (Nodes, Edges) {
     (nodes, edges)
 }
 
-fn calculate_cost(edges: &Edges, perm: Vec<u8>) -> Option<i32> {
-    let mut sum = 0;
-    for (a, b) in perm.into_iter().circular_tuple_windows::<(u8, u8)>() {
-        sum += edges.get(&(a, b))?;
-        sum += edges.get(&(b, a))?;
+/// Happiness change of sitting each pair of guests together, counting both of them. `None`
+/// if the notes don't say how one of them would feel, so they never sit together.
+/// With `me`, there is one more guest that doesn't care about anyone.
+fn happiness(input: &str, me: bool) -> Vec<Vec<Option<i32>>> {
+    let (nodes, mut edges) = parse(input);
+    let n = nodes.len() as u8;
+    if me {
+        for i in 0..n {
+            edges.insert((i, n), 0);
+            edges.insert((n, i), 0);
+        }
     }
-    Some(sum)
-}
-
-fn get_costs(input: &str) -> impl Iterator<Item=i32> {
-    let (nodes, edges) = parse(input);
-    (0..nodes.len() as u8).permutations(nodes.len())
-        .filter_map(move |perm| calculate_cost(&edges, perm))
+    let guests = if me { n + 1 } else { n };
+    (0..guests)
+        .map(|a| {
+            (0..guests)
+                .map(|b| Some(edges.get(&(a, b))? + edges.get(&(b, a))?))
+                .collect()
+        })
+        .collect()
 }
 
 pub fn part1(input: &str) -> i32 {
-    get_costs(input).max().unwrap()
+    best_cycle(&happiness(input, false), Goal::Max).unwrap().cost
 }
 
 pub fn part2(input: &str) -> i32 {
-    let (mut nodes, mut edges) = parse(input);
-    let my_id = nodes.len() as u8;
-    for i in 0..nodes.len() {
-        edges.insert((i as u8, my_id), 0);
-        edges.insert((my_id, i as u8), 0);
-    }
-    nodes.insert("me", my_id);
-    (0..nodes.len() as u8).permutations(nodes.len())
-        .filter_map(move |perm| calculate_cost(&edges, perm))
-        .max().unwrap()
+    best_cycle(&happiness(input, true), Goal::Max).unwrap().cost
 }
 
 #[test]
 fn test() {
     crate::test_2015!(13, 330);
+    // Nothing is known about Alice and Carol, so they can't sit together, unless I sit
+    // between them
+    let input = "\
+        Alice would gain 10 happiness units by sitting next to Bob.\n\
+        Bob would gain 1 happiness units by sitting next to Alice.\n\
+        Bob would lose 5 happiness units by sitting next to Carol.\n\
+        Carol would gain 2 happiness units by sitting next to Bob.\n";
+    assert_eq!(best_cycle(&happiness(input, false), Goal::Max), None);
+    assert_eq!(part2(input), 8);
 }
\ No newline at end of file
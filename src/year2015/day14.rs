@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::utils::ParseError;
+use crate::utils::{fields, parse_lines, ParseError};
 
 #[derive(Debug)]
 struct Reindeer {
@@ -33,11 +33,14 @@ impl FromStr for Reindeer {
     type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let seq = line.split_whitespace().collect::<Vec<_>>();
+        let f = fields(
+            line,
+            "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.",
+        )?;
         Ok(Reindeer {
-            speed: seq[3].parse()?,
-            move_t: seq[6].parse()?,
-            rest_t: seq[13].parse()?,
+            speed: f.parse(1)?,
+            move_t: f.parse(2)?,
+            rest_t: f.parse(3)?,
         })
     }
 }
@@ -61,8 +64,7 @@ fn test_reindeer_dist() {
 }
 
 fn parse(input: &str) -> Vec<Reindeer> {
-    let res: Result<Vec<_>, _> = input.lines().map(Reindeer::from_str).collect();
-    res.unwrap()
+    parse_lines(input, Reindeer::from_str).unwrap()
 }
 
 fn max_indexes(v: &[u32]) -> impl Iterator<Item=usize> + '_ {
@@ -1,83 +1,117 @@
 use std::str::FromStr;
 
-use crate::utils::ParseError;
+use crate::combinatorics::compositions;
+use crate::utils::{fields, parse_lines, ParseError};
 
-fn ways_to_sum() -> Vec<(i64, i64, i64, i64)> {
-    let mut res = vec![];
-    for a in 1..=97 {
-        for b in 1..=100 - a {
-            for c in 1..=100 - a - b - 1 {
-                res.push((a, b, c, 100 - a - b - c))
-            }
-        }
-    }
-    res
-}
+const TEASPOONS: u32 = 100;
 
 #[derive(Debug)]
-struct Ingredients {
-    cap: i64,
-    dur: i64,
-    fla: i64,
-    tex: i64,
-    cal: i64,
+struct Ingredient {
+    name: String,
+    /// Capacity, durability, flavor and texture
+    properties: [i64; 4],
+    calories: i64,
 }
 
-impl FromStr for Ingredients {
+impl FromStr for Ingredient {
     type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let helper = line.replace(',', "");
-        let seq = helper.split_whitespace().collect::<Vec<_>>();
-        Ok(Ingredients {
-            cap: seq[2].parse()?,
-            dur: seq[4].parse()?,
-            fla: seq[6].parse()?,
-            tex: seq[8].parse()?,
-            cal: seq[10].parse()?,
+        let f = fields(
+            line,
+            "{}: capacity {}, durability {}, flavor {}, texture {}, calories {}",
+        )?;
+        Ok(Ingredient {
+            name: f.get(0).to_string(),
+            properties: [f.parse(1)?, f.parse(2)?, f.parse(3)?, f.parse(4)?],
+            calories: f.parse(5)?,
         })
     }
 }
 
-fn parse(input: &str) -> Vec<Ingredients> {
-    let res: Result<Vec<_>, _> = input.lines().map(Ingredients::from_str).collect();
-    res.unwrap()
+fn parse(input: &str) -> Vec<Ingredient> {
+    parse_lines(input, Ingredient::from_str).unwrap()
 }
 
-macro_rules! sum_prop {
-    ($field:ident, $ing:expr, $tuple:expr) => {{
-        let (a, b, c, d) = $tuple;
-        let res = a * $ing[0].$field + b * $ing[1].$field + c * $ing[2].$field + d * $ing[3].$field;
-        if res > 0 { res } else { 0 }
-    }};
+/// Teaspoons of each ingredient, in the same order as the ingredients
+#[derive(Debug, PartialEq, Eq)]
+struct Recipe {
+    score: i64,
+    amounts: Vec<u32>,
 }
 
-fn apply_sum(sum: (i64, i64, i64, i64), ing: &[Ingredients]) -> i64 {
-    let cap = sum_prop!(cap, ing, sum);
-    let dur = sum_prop!(dur, ing, sum);
-    let fla = sum_prop!(fla, ing, sum);
-    let tex = sum_prop!(tex, ing, sum);
-    cap * dur * fla * tex
+/// Product of the total of each property, where a negative total counts as 0
+fn score(totals: [i64; 4]) -> i64 {
+    totals.iter().map(|t| (*t).max(0)).product()
 }
 
-fn is_500(sum: (i64, i64, i64, i64), ing: &[Ingredients]) -> bool {
-    sum_prop!(cal, ing, sum) == 500
+/// Best recipe using exactly `teaspoons`, according to `score`. With `calories`, only the
+/// recipes that have exactly that many calories count.
+fn best_recipe(
+    ingredients: &[Ingredient],
+    teaspoons: u32,
+    score: impl Fn([i64; 4]) -> i64,
+    calories: Option<i64>,
+) -> Option<Recipe> {
+    compositions(ingredients.len(), teaspoons)
+        .filter(|amounts| {
+            calories.is_none_or(|cal| {
+                let total: i64 = ingredients
+                    .iter()
+                    .zip(amounts)
+                    .map(|(ing, n)| ing.calories * *n as i64)
+                    .sum();
+                total == cal
+            })
+        })
+        .map(|amounts| {
+            let mut totals = [0; 4];
+            for (ing, n) in ingredients.iter().zip(&amounts) {
+                for (t, p) in totals.iter_mut().zip(ing.properties) {
+                    *t += p * *n as i64;
+                }
+            }
+            Recipe {
+                score: score(totals),
+                amounts,
+            }
+        })
+        .max_by_key(|recipe| recipe.score)
+}
+
+fn describe(ingredients: &[Ingredient], recipe: &Recipe) -> String {
+    ingredients
+        .iter()
+        .zip(&recipe.amounts)
+        .map(|(ing, n)| format!("{} {}", n, ing.name))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 pub fn part1(input: &str) -> i64 {
-    let ing = parse(input);
-    ways_to_sum().into_iter().map(|s| {
-        apply_sum(s, &ing)
-    }).max().unwrap()
+    best_recipe(&parse(input), TEASPOONS, score, None).unwrap().score
 }
 
 pub fn part2(input: &str) -> i64 {
-    let ing = parse(input);
-    ways_to_sum().into_iter().map(|s| {
-        if is_500(s, &ing) {
-            apply_sum(s, &ing)
-        } else {
-            0
-        }
-    }).max().unwrap()
+    best_recipe(&parse(input), TEASPOONS, score, Some(500)).unwrap().score
+}
+
+#[test]
+fn test() {
+    crate::test_2015!(15, 62842880, 57600000);
+
+    let ingredients = parse(&crate::utils::load_test_file(2015, 15));
+    let recipe = best_recipe(&ingredients, TEASPOONS, score, None).unwrap();
+    assert_eq!(recipe.amounts, vec![44, 56]);
+    assert_eq!(describe(&ingredients, &recipe), "44 Butterscotch, 56 Cinnamon");
+    let recipe = best_recipe(&ingredients, TEASPOONS, score, Some(500)).unwrap();
+    assert_eq!(recipe.amounts, vec![40, 60]);
+    // Any number of ingredients works, even one
+    assert_eq!(
+        best_recipe(&ingredients[..1], 10, |t| t[2], None),
+        Some(Recipe {
+            score: 60,
+            amounts: vec![10]
+        })
+    );
 }
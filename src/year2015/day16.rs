@@ -1,176 +1,162 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Range;
 use std::str::FromStr;
 
-use crate::utils::ParseError;
+use crate::utils::{fields, key_values, parse_lines, ParseError};
 
-#[derive(Debug)]
-struct Sue {
-    id: u32,
-    children: Option<u32>,
-    cats: Option<u32>,
-    samoyeds: Option<u32>,
-    pomeranians: Option<u32>,
-    akitas: Option<u32>,
-    vizslas: Option<u32>,
-    goldfish: Option<u32>,
-    trees: Option<u32>,
-    cars: Option<u32>,
-    perfumes: Option<u32>,
+/// What the MFCSAM found in the gift
+const MFCSAM: &str = "\
+children: 3
+cats: 7
+samoyeds: 2
+pomeranians: 3
+akitas: 0
+vizslas: 0
+goldfish: 5
+trees: 3
+cars: 2
+perfumes: 1";
+
+/// How the readings of the outdated retroencabulator must be compared in part 2. The ones
+/// that are not here must be equal.
+const RETROENCABULATOR: &str = "\
+cats: >
+trees: >
+pomeranians: <
+goldfish: <";
+
+/// How a value that a Sue remembers is compared with the reading of the MFCSAM
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Comparator {
+    Eq,
+    Gt,
+    Lt,
+    /// Must be inside the range, whatever the reading was
+    Range(Range<u32>),
 }
 
-const CORRECT_SUE: Sue = Sue {
-    id: 0,
-    children: Some(3),
-    cats: Some(7),
-    samoyeds: Some(2),
-    pomeranians: Some(3),
-    akitas: Some(0),
-    vizslas: Some(0),
-    goldfish: Some(5),
-    trees: Some(3),
-    cars: Some(2),
-    perfumes: Some(1),
-};
-
-macro_rules! has_same_prop {
-    ($res:ident, $obj:ident, $other:ident, $prop:ident) => {
-        if let Some(prop) = $other.$prop {
-            $res = $res && match $obj.$prop {
-                Some(c) => c == prop,
-                None => $res,
-            }
+impl Comparator {
+    fn accepts(&self, reading: Option<u32>, value: u32) -> bool {
+        match (self, reading) {
+            (Comparator::Range(range), _) => range.contains(&value),
+            // Nothing was found about it, so any value is fine
+            (_, None) => true,
+            (Comparator::Eq, Some(reading)) => value == reading,
+            (Comparator::Gt, Some(reading)) => value > reading,
+            (Comparator::Lt, Some(reading)) => value < reading,
         }
-    };
+    }
 }
 
-macro_rules! has_higher_prop {
-    ($res:ident, $obj:ident, $other:ident, $prop:ident) => {
-        if let Some(prop) = $other.$prop {
-            $res = $res && match $obj.$prop {
-                Some(c) => c > prop,
-                None => $res,
+impl FromStr for Comparator {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "=" => Ok(Comparator::Eq),
+            ">" => Ok(Comparator::Gt),
+            "<" => Ok(Comparator::Lt),
+            _ => {
+                let f = fields(s, "{}..{}")?;
+                Ok(Comparator::Range(f.parse(0)?..f.parse(1)?))
             }
         }
-    };
+    }
 }
 
-macro_rules! has_lower_prop {
-    ($res:ident, $obj:ident, $other:ident, $prop:ident) => {
-        if let Some(prop) = $other.$prop {
-            $res = $res && match $obj.$prop {
-                Some(c) => c < prop,
-                None => $res,
-            }
-        }
-    };
+/// Lines of `name: value`, like the MFCSAM readout or the comparison rules
+fn spec<T>(text: &str) -> Result<HashMap<String, T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let pairs = parse_lines(text, |line| {
+        let f = fields(line, "{}: {}")?;
+        Ok((f.get(0).to_string(), f.parse(1)?))
+    })?;
+    Ok(pairs.into_iter().collect())
 }
 
-impl Sue {
-    fn new(id: u32) -> Sue {
-        Sue {
-            id,
-            children: None,
-            cats: None,
-            samoyeds: None,
-            pomeranians: None,
-            akitas: None,
-            vizslas: None,
-            goldfish: None,
-            trees: None,
-            cars: None,
-            perfumes: None,
-        }
-    }
-    
-    fn set_prop(&mut self, name: &str, val: u32) {
-        match name {
-            "children" => self.children = Some(val),
-            "cats" => self.cats = Some(val),
-            "samoyeds" => self.samoyeds = Some(val),
-            "pomeranians" => self.pomeranians = Some(val),
-            "akitas" => self.akitas = Some(val),
-            "vizslas" => self.vizslas = Some(val),
-            "goldfish" => self.goldfish = Some(val),
-            "trees" => self.trees = Some(val),
-            "cars" => self.cars = Some(val),
-            "perfumes" => self.perfumes = Some(val),
-            _ => panic!("There is no field named {}", name)
-        }
-    }
-    
-    fn is_match_part1(&self, other: &Sue) -> bool {
-        let mut res = true;
-        has_same_prop!(res, self, other, children);
-        has_same_prop!(res, self, other, cats);
-        has_same_prop!(res, self, other, samoyeds);
-        has_same_prop!(res, self, other, pomeranians);
-        has_same_prop!(res, self, other, akitas);
-        has_same_prop!(res, self, other, vizslas);
-        has_same_prop!(res, self, other, goldfish);
-        has_same_prop!(res, self, other, trees);
-        has_same_prop!(res, self, other, cars);
-        has_same_prop!(res, self, other, perfumes);
-        res
-    }
-    
-    fn is_match_part2(&self, other: &Sue) -> bool {
-        let mut res = true;
-        has_same_prop!(res, self, other, children);
-        has_lower_prop!(res, self, other, cats);
-        has_same_prop!(res, self, other, samoyeds);
-        has_higher_prop!(res, self, other, pomeranians);
-        has_same_prop!(res, self, other, akitas);
-        has_same_prop!(res, self, other, vizslas);
-        has_higher_prop!(res, self, other, goldfish);
-        has_lower_prop!(res, self, other, trees);
-        has_same_prop!(res, self, other, cars);
-        has_same_prop!(res, self, other, perfumes);
-        res
-    }
+#[derive(Debug)]
+struct Sue {
+    id: u32,
+    attributes: HashMap<String, u32>,
 }
 
 impl FromStr for Sue {
     type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let helper = line.replace([',', ':'], "");
-        let seq = helper.split_whitespace().collect::<Vec<_>>();
-        let mut sue = Sue::new(seq[1].parse()?);
-        sue.set_prop(seq[2], seq[3].parse()?);
-        sue.set_prop(seq[4], seq[5].parse()?);
-        sue.set_prop(seq[6], seq[7].parse()?);
-        Ok(sue)
+        let f = fields(line, "Sue {}: {}")?;
+        let attributes = key_values(f.get(1), ": ", ", ")?
+            .into_iter()
+            .map(|(name, val)| Ok((name.to_string(), val.parse()?)))
+            .collect::<Result<_, ParseError>>()?;
+        Ok(Sue {
+            id: f.parse(0)?,
+            attributes,
+        })
+    }
+}
+
+/// What is known about the right Sue
+struct Scanner {
+    readout: HashMap<String, u32>,
+    rules: HashMap<String, Comparator>,
+}
+
+impl Scanner {
+    fn new(readout: &str, rules: &str) -> Result<Self, ParseError> {
+        Ok(Scanner {
+            readout: spec(readout)?,
+            rules: spec(rules)?,
+        })
+    }
+
+    fn is_match(&self, sue: &Sue) -> bool {
+        sue.attributes.iter().all(|(name, value)| {
+            let comparator = self.rules.get(name).unwrap_or(&Comparator::Eq);
+            comparator.accepts(self.readout.get(name).copied(), *value)
+        })
     }
 }
 
 #[test]
 fn test_same_sue() {
-    let mut other = Sue::new(12);
-    
-    other.set_prop("cats", 7);
-    assert_eq!(CORRECT_SUE.is_match_part1(&other), true);
-    
-    other.set_prop("goldfish", 5);
-    assert_eq!(CORRECT_SUE.is_match_part1(&other), true);
-    
-    other.set_prop("vizslas", 1);
-    assert_eq!(CORRECT_SUE.is_match_part1(&other), false);
-    
-    other.set_prop("vizslas", 0);
-    assert_eq!(CORRECT_SUE.is_match_part1(&other), true);
-}
+    let scanner = Scanner::new(MFCSAM, "").unwrap();
+    let sue = |line: &str| line.parse::<Sue>().unwrap();
+
+    assert!(scanner.is_match(&sue("Sue 12: cats: 7")));
+    assert!(scanner.is_match(&sue("Sue 12: cats: 7, goldfish: 5")));
+    assert!(!scanner.is_match(&sue("Sue 12: cats: 7, goldfish: 5, vizslas: 1")));
+    assert!(scanner.is_match(&sue("Sue 12: cats: 7, goldfish: 5, vizslas: 0")));
+    assert!(scanner.is_match(&sue("Sue 12: unicorns: 4")));
 
-fn parse(input: &str) -> impl Iterator<Item = Sue> + '_ {
-    input.lines().map(|line| Sue::from_str(line).unwrap())
+    let scanner = Scanner::new(MFCSAM, "cats: >\nunicorns: 2..5").unwrap();
+    assert!(!scanner.is_match(&sue("Sue 1: cats: 7")));
+    assert!(scanner.is_match(&sue("Sue 1: cats: 8, unicorns: 4")));
+    assert!(!scanner.is_match(&sue("Sue 1: unicorns: 5")));
+    assert_eq!(
+        Scanner::new(MFCSAM, "cats: >=").err().unwrap().to_string(),
+        "Line 1: Invalid value '>=' at column 7 of 'cats: >=': Expected '..' at column 1 of '>='"
+    );
 }
 
-fn solve(input: &str, match_func: fn(&Sue, &Sue) -> bool) -> u32 {
-    parse(input).filter(|s| match_func(&CORRECT_SUE, s)).map(|s| s.id).next().unwrap()
+fn solve(input: &str, rules: &str) -> u32 {
+    let scanner = Scanner::new(MFCSAM, rules).unwrap();
+    input
+        .lines()
+        .map(|line| Sue::from_str(line).unwrap())
+        .find(|sue| scanner.is_match(sue))
+        .map(|sue| sue.id)
+        .unwrap()
 }
 
 pub fn part1(input: &str) -> u32 {
-    solve(input, Sue::is_match_part1)
+    solve(input, "")
 }
 
 pub fn part2(input: &str) -> u32 {
-    solve(input, Sue::is_match_part2)
+    solve(input, RETROENCABULATOR)
 }
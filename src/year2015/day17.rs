@@ -1,48 +1,35 @@
-use itertools::Itertools;
+use crate::combinatorics::{count_subsets, count_subsets_by_size};
 
 fn parse(input: &str) -> Vec<u32> {
-    input
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .sorted()
-        .collect()
+    input.lines().map(|line| line.parse().unwrap()).collect()
 }
 
-fn fill(containers: &[u32], to_fill: u32) -> Vec<Vec<u32>> {
-    if to_fill == 0 {
-        return vec![vec![]];
-    }
-    if containers.is_empty() {
-        return vec![];
-    }
-    let mut res = vec![];
-    for (i, c) in containers.iter().enumerate() {
-        if to_fill < *c {
-            break;
-        }
-        res.extend(
-            fill(&containers[i + 1..], to_fill - c)
-                .into_iter()
-                .map(|mut v| {
-                    v.push(*c);
-                    v
-                }),
-        )
-    }
-    res
+const EGGNOG: u32 = 150;
+
+fn combinations(containers: &[u32], to_fill: u32) -> usize {
+    count_subsets(containers, to_fill)
+}
+
+/// Combinations that use the fewest containers
+fn min_combinations(containers: &[u32], to_fill: u32) -> usize {
+    count_subsets_by_size(containers, to_fill)
+        .into_iter()
+        .find(|ways| *ways > 0)
+        .unwrap_or(0)
 }
 
 pub fn part1(input: &str) -> usize {
-    fill(&parse(input), 150).len()
+    combinations(&parse(input), EGGNOG)
 }
 
 pub fn part2(input: &str) -> usize {
-    let res = fill(&parse(input), 150);
-    let min_len = res.iter().map(|v| v.len()).min().unwrap();
-    res.iter().filter(|v| v.len() == min_len).count()
+    min_combinations(&parse(input), EGGNOG)
 }
 
 #[test]
 fn test() {
     crate::test_2015!(17, 4, 3);
+    let containers = [20, 15, 10, 5, 5];
+    assert_eq!(combinations(&containers, 25), 4);
+    assert_eq!(min_combinations(&containers, 25), 3);
 }
@@ -1,11 +1,7 @@
 use std::str::FromStr;
 
-use grid::Grid;
-use itertools::{iproduct, Itertools};
-
-use crate::utils::{MyGrid, ParseError, Point};
-
-use rayon::prelude::*;
+use crate::automaton::LifeLike;
+use crate::utils::{BitGrid, MyGrid, ParseError, Point};
 
 #[derive(Default, PartialEq, Eq, Clone, Copy)]
 enum Light {
@@ -26,76 +22,28 @@ impl std::fmt::Debug for Light {
 #[derive(Debug)]
 struct Lights {
     grid: MyGrid<Light>,
-    width: usize,
-    height: usize,
 }
 
 impl FromStr for Lights {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut grid = MyGrid(Grid::new(0, 0));
-        input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        '#' => Light::On,
-                        '.' => Light::Off,
-                        _ => panic!("Unknown light char: {}", c),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .for_each(|row| grid.push_row(row));
-        Ok(Lights {
-            width: grid.cols(),
-            height: grid.rows(),
-            grid,
-        })
+        let grid = MyGrid::try_parse(input, |c| match c {
+            b'#' => Some(Light::On),
+            b'.' => Some(Light::Off),
+            _ => None,
+        })?;
+        Ok(Lights { grid })
     }
 }
 
 impl Lights {
-    fn tick(&mut self) {
-        let bla = iproduct!(0..self.height, 0..self.width)
-            .collect_vec()
-            .into_par_iter()
-            .map(Point::from)
-            .map(|point| {
-                let on_lights = point
-                    .all_neighbors()
-                    .into_iter()
-                    .filter(|p| self.grid.contains(p))
-                    .map(|p| self.grid[&p])
-                    .filter(|l| *l == Light::On)
-                    .count();
-                match self.grid[&point] {
-                    Light::Off => {
-                        if on_lights == 3 {
-                            Light::On
-                        } else {
-                            Light::Off
-                        }
-                    }
-                    Light::On => {
-                        if on_lights == 2 || on_lights == 3 {
-                            Light::On
-                        } else {
-                            Light::Off
-                        }
-                    }
-                }
-            })
-            .collect::<Vec<_>>();
-        self.grid = MyGrid(Grid::from_vec(bla, self.width))
-    }
-
-    fn part2_tick(&mut self) {
-        self.tick();
-        self.grid[(0_isize, 0)] = Light::On;
-        self.grid[(self.height - 1, 0)] = Light::On;
-        self.grid[(0, self.width - 1)] = Light::On;
-        self.grid[(self.height - 1, self.width - 1)] = Light::On;
+    fn to_bits(&self) -> BitGrid {
+        let mut bits = BitGrid::new(self.grid.rows(), self.grid.cols());
+        for ((r, c), light) in self.grid.indexed_iter() {
+            bits.set(&Point::from((r, c)), *light == Light::On);
+        }
+        bits
     }
 }
 
@@ -103,27 +51,39 @@ fn parse(input: &str) -> Lights {
     Lights::from_str(input).unwrap()
 }
 
-fn solve(input: &str, tick_fn: fn(&mut Lights) -> ()) -> usize {
-    let mut lights = parse(input);
-    for _ in 0..100 {
-        tick_fn(&mut lights);
+/// Runs `steps` generations of Conway's game of life, turning on the `stuck` lights after
+/// each one
+fn animate(mut bits: BitGrid, steps: usize, stuck: &[Point]) -> usize {
+    let rule = LifeLike::conway();
+    for p in stuck {
+        bits.set(p, true);
+    }
+    for _ in 0..steps {
+        bits = bits.step(|alive, n| rule.next(alive, n));
+        for p in stuck {
+            bits.set(p, true);
+        }
     }
-    lights
-        .grid
-        .iter()
-        .filter(|light| **light == Light::On)
-        .count()
+    bits.count_ones()
 }
 
 pub fn part1(input: &str) -> usize {
-    solve(input, Lights::tick)
+    animate(parse(input).to_bits(), 100, &[])
 }
 
 pub fn part2(input: &str) -> usize {
-    solve(input, Lights::part2_tick)
+    let bits = parse(input).to_bits();
+    let (rows, cols) = (bits.rows(), bits.cols());
+    let corners = [(0, 0), (0, cols - 1), (rows - 1, 0), (rows - 1, cols - 1)].map(Point::from);
+    animate(bits, 100, &corners)
 }
 
 #[test]
 fn test() {
     crate::test_2015!(18, 4);
+
+    let bits = parse(&crate::utils::load_test_file(2015, 18)).to_bits();
+    assert_eq!(animate(bits.clone(), 4, &[]), 4);
+    let corners = [(0, 0), (0, 5), (5, 0), (5, 5)].map(Point::from);
+    assert_eq!(animate(bits, 5, &corners), 17);
 }
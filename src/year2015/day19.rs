@@ -8,7 +8,7 @@ struct Mutation<'a> {
 }
 
 impl Mutation<'_> {
-    fn parse(s: &str) -> Mutation {
+    fn parse(s: &str) -> Mutation<'_> {
         let tmp: Vec<_> = s.trim().split(" => ").collect();
         if tmp[0].len() != 1 {
             panic!("More than one char in source");
@@ -33,7 +33,7 @@ fn preprocess(input: &str) -> String {
         .replace('Y', ",")
 }
 
-fn parse(input: &str) -> (&str, Vec<Mutation>) {
+fn parse(input: &str) -> (&str, Vec<Mutation<'_>>) {
     let mut lines = input.lines();
     let mutations: Vec<_> = lines
         .take_while_ref(|line| line.contains("=>"))
@@ -1,30 +1,21 @@
-use itertools::Itertools;
-use lazy_static::lazy_static;
-use regex::{Match, Regex};
+use crate::regex_struct;
 
-lazy_static! {
-    static ref BOX_REGEX: Regex = Regex::new(r"(\d+)x(\d+)x(\d+)").unwrap();
-}
-
-fn get_val(x: &Match) -> u64 {
-    x.as_str().parse::<u64>().expect("Should be parsable")
-}
-
-fn ordered_tuple(a: &Match, b: &Match, c: &Match) -> Option<(u64, u64, u64)> {
-    let mut values = vec![get_val(a), get_val(b), get_val(c)];
-    values.sort();
-    values.into_iter().collect_tuple()
+regex_struct! {
+    #[regex(r"(?P<l>\d+)x(?P<w>\d+)x(?P<h>\d+)")]
+    struct Present {
+        l: u64,
+        w: u64,
+        h: u64,
+    }
 }
 
+/// The dimensions of every present, from smallest to biggest
 fn parse(input: &str) -> impl Iterator<Item=(u64, u64, u64)> + '_ {
     input.lines().map(|line| {
-        BOX_REGEX
-            .captures(line)
-            .and_then(|cap| match (cap.get(1), cap.get(2), cap.get(3)) {
-                (Some(a), Some(b), Some(c)) => ordered_tuple(&a, &b, &c),
-                _ => None,
-            })
-            .expect("Every line should be correctly parsed")
+        let Present { l, w, h } = line.parse().unwrap();
+        let mut values = [l, w, h];
+        values.sort();
+        (values[0], values[1], values[2])
     })
 }
 
@@ -1,10 +1,9 @@
-use std::collections::HashSet;
+use crate::utils::{Point, SparseGrid};
 
-use crate::utils::Point;
-
-fn navigate(chars: impl Iterator<Item=char>, positions: &mut HashSet<Point>) {
+/// Counts how many presents each house gets
+fn navigate(chars: impl Iterator<Item=char>, houses: &mut SparseGrid<u32>) {
     let mut current_pos = Point::ZERO;
-    positions.insert(current_pos.clone());
+    *houses.get_mut(&current_pos) += 1;
 
     for c in chars {
         let new_pos = match c {
@@ -14,22 +13,22 @@ fn navigate(chars: impl Iterator<Item=char>, positions: &mut HashSet<Point>) {
             '<' => current_pos.left(),
             _ => panic!("This should not happen"),
         };
-        positions.insert(new_pos.clone());
+        *houses.get_mut(&new_pos) += 1;
         current_pos = new_pos;
     }
 }
 
 pub fn part1(input: &str) -> usize {
-    let mut positions = HashSet::<Point>::new();
-    navigate(input.chars(), &mut positions);
-    positions.len()
+    let mut houses = SparseGrid::new();
+    navigate(input.chars(), &mut houses);
+    houses.len()
 }
 
 pub fn part2(input: &str) -> usize {
-    let mut positions = HashSet::<Point>::new();
-    navigate(input.chars().step_by(2), &mut positions);
-    navigate(input.chars().skip(1).step_by(2), &mut positions);
-    positions.len()
+    let mut houses = SparseGrid::new();
+    navigate(input.chars().step_by(2), &mut houses);
+    navigate(input.chars().skip(1).step_by(2), &mut houses);
+    houses.len()
 }
 
 #[test]
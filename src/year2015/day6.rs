@@ -2,43 +2,15 @@ use std::str::FromStr;
 
 use grid::Grid;
 use itertools::Itertools;
-use lazy_static::lazy_static;
-use regex::{Match, Regex};
-
-use crate::utils::{MyGrid, ParseError, Point};
-
-lazy_static! {
-    static ref COORDS_REGEX: Regex = Regex::new(r"(\d+),(\d+) through (\d+),(\d+)").unwrap();
-}
-
-#[derive(Debug)]
-struct Coords {
-    top: Point,
-    bottom: Point,
-}
-
-impl FromStr for Coords {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn to_point(x: Match, y: Match) -> Option<Point> {
-            let a = x.as_str().parse::<isize>().ok()?;
-            let b = y.as_str().parse::<isize>().ok()?;
-            Some(Point::from((b, a)))
-        }
-
-        COORDS_REGEX
-            .captures(s)
-            .and_then(
-                |cap| match (cap.get(1), cap.get(2), cap.get(3), cap.get(4)) {
-                    (Some(a), Some(b), Some(c), Some(d)) => Some(Coords {
-                        top: to_point(a, b)?,
-                        bottom: to_point(c, d)?,
-                    }),
-                    _ => None,
-                },
-            )
-            .ok_or(ParseError::new("Impossible to parse Coords"))
+use crate::regex_struct;
+use crate::utils::{BitGrid, MyGrid, ParseError, Point};
+
+regex_struct! {
+    #[regex(r"(?P<top>\d+,\d+) through (?P<bottom>\d+,\d+)")]
+    #[derive(Debug)]
+    struct Coords {
+        top: Point,
+        bottom: Point,
     }
 }
 
@@ -74,47 +46,139 @@ impl<T> ExecInstruction<T> for MyGrid<T> {
     }
 }
 
+fn parse(input: &str) -> impl Iterator<Item = (Instruction, Coords)> + '_ {
+    input
+        .lines()
+        .map(|line| match (Instruction::from_str(line), Coords::from_str(line)) {
+            (Ok(inst), Ok(coords)) => (inst, coords),
+            _ => panic!("Faulty instruction"),
+        })
+}
+
+/// Applies every instruction to each light of a 1000x1000 grid
 fn solve<T>(input: &str, make_change: impl Fn(Instruction) -> Box<dyn Fn(&T) -> T>) -> MyGrid<T>
 where
     T: Default,
 {
     let mut grid: MyGrid<T> = MyGrid(Grid::new(1000, 1000));
-    input
-        .lines()
-        .map(|line| (Instruction::from_str(line), Coords::from_str(line)))
-        .for_each(|info| match info {
-            (Ok(inst), Ok(coords)) => grid.exec(coords, &make_change(inst)),
-            _ => panic!("Faulty instruction"),
-        });
+    parse(input).for_each(|(inst, coords)| grid.exec(coords, &make_change(inst)));
     grid
 }
 
-pub fn part1(input: &str) -> usize {
-    let grid = solve(input, |inst| {
-        Box::new(move |x| match inst {
-            Instruction::TurnOff => false,
-            Instruction::TurnOn => true,
-            Instruction::Toggle => !x,
+/// The lights split in the biggest rectangles that always end up in the same state.
+///
+/// Only the corners of the instructions can start a new block, so there are far fewer
+/// blocks than lights.
+struct Blocks<T> {
+    /// Where each column of blocks starts, and where the last one ends
+    xs: Vec<isize>,
+    /// Where each row of blocks starts, and where the last one ends
+    ys: Vec<isize>,
+    blocks: MyGrid<T>,
+}
+
+impl<T> Blocks<T> {
+    /// Every block with the number of lights in it
+    fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.blocks.indexed_iter().map(|((r, c), x)| {
+            let area = (self.xs[c + 1] - self.xs[c]) * (self.ys[r + 1] - self.ys[r]);
+            (x, area as usize)
         })
-    });
-    grid.iter().filter(|x| **x).count()
+    }
 }
 
-pub fn part2(input: &str) -> usize {
-    let grid = solve(input, |inst| {
-        Box::new(move |x| match inst {
-            Instruction::TurnOff => {
-                if x > &1 {
-                    x - 1
-                } else {
-                    0
-                }
+/// Same as [`solve`], but changing whole blocks of lights at once
+fn solve_blocks<T>(
+    input: &str,
+    make_change: impl Fn(Instruction) -> Box<dyn Fn(&T) -> T>,
+) -> Blocks<T>
+where
+    T: Default,
+{
+    let instructions = parse(input).collect_vec();
+    let edges = |coord: fn(&Point) -> isize| {
+        instructions
+            .iter()
+            .flat_map(|(_, coords)| [coord(&coords.top), coord(&coords.bottom) + 1])
+            .chain([0, 1000])
+            .sorted()
+            .dedup()
+            .collect_vec()
+    };
+    let xs = edges(Point::col);
+    let ys = edges(Point::row);
+    let mut blocks: MyGrid<T> = MyGrid(Grid::new(ys.len() - 1, xs.len() - 1));
+    let index = |edges: &[isize], val: isize| edges.binary_search(&val).unwrap();
+    for (inst, coords) in instructions {
+        let change = make_change(inst);
+        for r in index(&ys, coords.top.y)..index(&ys, coords.bottom.y + 1) {
+            for c in index(&xs, coords.top.x)..index(&xs, coords.bottom.x + 1) {
+                blocks[(r, c)] = change(&blocks[(r, c)]);
             }
-            Instruction::TurnOn => x + 1,
-            Instruction::Toggle => x + 2,
-        })
-    });
-    grid.iter().sum()
+        }
+    }
+    Blocks { xs, ys, blocks }
+}
+
+fn on_off(inst: Instruction) -> Box<dyn Fn(&bool) -> bool> {
+    Box::new(move |x| match inst {
+        Instruction::TurnOff => false,
+        Instruction::TurnOn => true,
+        Instruction::Toggle => !x,
+    })
+}
+
+fn brightness(inst: Instruction) -> Box<dyn Fn(&usize) -> usize> {
+    Box::new(move |x| match inst {
+        Instruction::TurnOff => x.saturating_sub(1),
+        Instruction::TurnOn => x + 1,
+        Instruction::Toggle => x + 2,
+    })
+}
+
+pub fn part1(input: &str) -> usize {
+    let mut grid = BitGrid::new(1000, 1000);
+    for (inst, Coords { top, bottom }) in parse(input) {
+        match inst {
+            Instruction::TurnOff => grid.set_rect(&top, &bottom, false),
+            Instruction::TurnOn => grid.set_rect(&top, &bottom, true),
+            Instruction::Toggle => grid.toggle_rect(&top, &bottom),
+        }
+    }
+    grid.count_ones()
+}
+
+pub fn part2(input: &str) -> usize {
+    solve_blocks(input, brightness)
+        .iter()
+        .map(|(x, area)| x * area)
+        .sum()
+}
+
+#[test]
+fn test_engines() {
+    use indoc::indoc;
+
+    let input = indoc! {"
+        turn on 0,0 through 999,999
+        toggle 0,0 through 999,0
+        turn off 499,499 through 500,500
+        toggle 10,20 through 600,700
+        turn on 5,5 through 15,990
+        turn off 300,0 through 300,999
+        toggle 0,400 through 999,400
+        turn on 999,999 through 999,999
+    "};
+    let blocks = solve_blocks(input, on_off);
+    assert_eq!(
+        blocks.iter().filter(|(x, _)| **x).map(|(_, area)| area).sum::<usize>(),
+        solve(input, on_off).iter().filter(|x| **x).count()
+    );
+    assert_eq!(
+        blocks.iter().filter(|(x, _)| **x).map(|(_, area)| area).sum::<usize>(),
+        part1(input)
+    );
+    assert_eq!(part2(input), solve(input, brightness).iter().sum::<usize>());
 }
 
 #[test]
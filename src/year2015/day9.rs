@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
-use itertools::Itertools;
+use crate::tsp::{best_path, Goal};
 
 type Nodes<'a> = HashMap<&'a str, u32>;
 type Edges = HashMap<(u32, u32), u32>;
 
-fn parse(input: &str) -> (Nodes, Edges) {
+fn parse(input: &str) -> (Nodes<'_>, Edges) {
     let mut nodes: HashMap<&str, u32> = HashMap::new();
     let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
     let mut curr = 0;
@@ -31,29 +31,31 @@ fn parse(input: &str) -> (Nodes, Edges) {
     (nodes, edges)
 }
 
-fn calculate_cost(edges: &Edges, perm: Vec<u32>) -> Option<u32> {
-    let mut sum = 0;
-    for win in perm.into_iter().tuple_windows::<(u32, u32)>() {
-        sum += edges.get(&win)?;
-    }
-    Some(sum)
-}
-
-fn get_costs(input: &str) -> impl Iterator<Item=u32> {
+/// Distance between every pair of cities, in a matrix. `None` if there is no route between
+/// them, so no path goes that way.
+fn distances(input: &str) -> Vec<Vec<Option<u32>>> {
     let (nodes, edges) = parse(input);
-    (0_u32..nodes.len() as u32).permutations(nodes.len())
-        .filter_map(move |perm| calculate_cost(&edges, perm))
+    (0..nodes.len() as u32)
+        .map(|a| {
+            (0..nodes.len() as u32)
+                .map(|b| edges.get(&(a, b)).copied())
+                .collect()
+        })
+        .collect()
 }
 
 pub fn part1(input: &str) -> u32 {
-    get_costs(input).min().unwrap()
+    best_path(&distances(input), Goal::Min).unwrap().cost
 }
 
 pub fn part2(input: &str) -> u32 {
-    get_costs(input).max().unwrap()
+    best_path(&distances(input), Goal::Max).unwrap().cost
 }
 
 #[test]
 fn test() {
-    crate::test_2015!(9, 605, 982)
+    crate::test_2015!(9, 605, 982);
+    // Dublin and Belfast are not connected, so it has to go through London
+    let input = "London to Dublin = 464\nLondon to Belfast = 518\n";
+    assert_eq!(part1(input), 982);
 }
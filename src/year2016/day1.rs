@@ -1,7 +1,5 @@
-use std::collections::HashSet;
-
 use crate::utils::Rotation::*;
-use crate::utils::{Direction, Point, Rotation};
+use crate::utils::{Direction, Point, Rotation, Segment};
 
 struct Turn {
     r: Rotation,
@@ -34,19 +32,27 @@ pub fn part1(input: &str) -> usize {
 }
 
 pub fn part2(input: &str) -> usize {
-    let mut pos = Point::ZERO;
     let mut dir = Direction::Up;
-    let mut visited = HashSet::<Point>::new();
-    visited.insert(pos.clone());
-    'outer: for Turn { r, n } in parse(input) {
+    let mut walked: Vec<Segment> = vec![];
+    let mut pos = Point::ZERO;
+    for Turn { r, n } in parse(input) {
         dir = dir.rotate(r);
-        for _ in 0..n {
-            pos = pos.move1(&dir);
-            if visited.contains(&pos) {
-                break 'outer;
-            }
-            visited.insert(pos.clone());
+        let segment = Segment::new(pos.clone(), pos.move_in(&dir, n as isize));
+        // The start was already visited, so it doesn't count. Checking all the segments that
+        // touch this one, as after a move of 0 the previous one can be on the same line.
+        let crossed = walked
+            .iter()
+            .filter(|other| segment.intersection(other).is_some())
+            .collect::<Vec<_>>();
+        let revisited = segment
+            .cells()
+            .skip(1)
+            .find(|p| crossed.iter().any(|other| other.contains(p)));
+        if let Some(p) = revisited {
+            return p.manhathan_dist(&Point::ZERO);
         }
+        pos = segment.end.clone();
+        walked.push(segment);
     }
     pos.manhathan_dist(&Point::ZERO)
 }
@@ -58,4 +64,6 @@ fn test() {
     assert_eq!(part1("R5, L5, R5, R3"), 12);
 
     assert_eq!(part2("R8, R4, R4, R8"), 4);
+    // Going back over the first move, (1, 0) is the first place visited twice
+    assert_eq!(part2("R2, L0, L3"), 1);
 }
@@ -1,99 +1,90 @@
-use std::collections::VecDeque;
-
-use grid::Grid;
-use itertools::iproduct;
-
+use crate::search::{bfs, SearchResult};
 use crate::utils::{MyGrid, Point};
 
 #[derive(Debug)]
 struct Map {
-    width: usize,
-    height: usize,
     content: MyGrid<u8>,
     start: Point,
     end: Point,
 }
 
-impl Map {
-    fn contains(&self, point: &Point) -> bool {
-        self.content.contains(point)
-    }
-}
-
 fn parse(input: &str) -> Map {
-    let mut grid: MyGrid<u8> = MyGrid(Grid::new(0, 0));
-    let mut start = None;
-    let mut end = None;
-    for (r, line) in input.lines().enumerate() {
-        grid.push_row(line.chars().enumerate().map(|(c, char)| {
-            match char {
-                'S' => {
-                    start = Some(Point::from((r, c)));
-                    1
-                }
-                'E' => {
-                    end = Some(Point::from((r, c)));
-                    26
-                }
-                'a'..='z' => char as u8 - b'a' + 1,
-                _ => panic!("This character should not be here")
-            }
-        }).collect());
-    }
+    let (grid, markers) = MyGrid::try_parse_with_markers(
+        input,
+        |c| c.is_ascii_lowercase().then(|| c - b'a' + 1),
+        &[('S', 1), ('E', 26)],
+    )
+    .unwrap();
+    let marker = |c| markers.get(&c).and_then(|points| points.first()).cloned();
     Map {
-        width: grid.cols(),
-        height: grid.rows(),
+        start: marker('S').expect("There was no start on the provided map"),
+        end: marker('E').expect("There was no end on the provided map"),
         content: grid,
-        start: start.expect("There was no start on the provided map"),
-        end: end.expect("There was no end on the provided map"),
     }
 }
 
-fn get_neighbours<'a>(coord: &Point, map: &'a Map) -> impl Iterator<Item=Point> + 'a {
-    coord.neighbors().into_iter()
-        .filter(|points| map.contains(points))
-}
-
 fn can_move(map: &Map, current: &Point, next: &Point) -> bool {
     let from = map.content[current];
     let to = map.content[next];
     from <= to + 1
 }
 
-fn get_cost_map(map: &Map) -> MyGrid<u16> {
-    let mut cost: MyGrid<u16> = MyGrid(Grid::init(map.height, map.width, u16::MAX));
-    let mut to_analyze = VecDeque::new();
-    to_analyze.push_back((map.end.clone(), 0));
-    cost[&map.end] = 0;
+/// Distance from every point to the end
+fn get_cost_map(map: &Map) -> SearchResult<Point, usize> {
+    bfs(
+        [map.end.clone()],
+        |next| {
+            let next = next.clone();
+            map.content
+                .neighbors(&next)
+                .filter(move |point| can_move(map, &next, point))
+        },
+        |_| false,
+    )
+}
 
-    while let Some((next, current_cost)) = to_analyze.pop_front() {
-        get_neighbours(&next, map).for_each(|point| {
-            let new_cost = current_cost + 1;
-            if can_move(map, &next, &point) && new_cost < cost[&point] {
-                cost[&point] = new_cost;
-                to_analyze.push_back((point, new_cost));
-            }
-        })
-    }
-    cost
+/// The points of one of the shortest paths from the start to the end
+fn shortest_path(map: &Map, cost: &SearchResult<Point, usize>) -> Vec<Point> {
+    let mut path = cost.path_to(&map.start).expect("The start should be reachable");
+    // The search goes from the end to the start
+    path.reverse();
+    path
 }
 
-pub fn part1(input: &str) -> u16 {
+pub fn part1(input: &str) -> usize {
     let map = parse(input);
     let cost = get_cost_map(&map);
-    cost[&map.start]
+    *cost.cost(&map.start).expect("The start should be reachable")
 }
 
-pub fn part2(input: &str) -> u16 {
+pub fn part2(input: &str) -> usize {
     let map = parse(input);
     let cost = get_cost_map(&map);
-    iproduct!(0..map.height, 0..map.width)
-        .filter(|point| map.content[*point] == 1)
-        .map(|point| cost[point])
-        .min().expect("There should be min")
+    map.content
+        .indexed_iter()
+        .filter(|(_, height)| **height == 1)
+        .filter_map(|(point, _)| cost.cost(&Point::from(point)))
+        .min()
+        .copied()
+        .expect("There should be min")
 }
 
 #[test]
 fn test() {
     crate::test_2022!(12, 31, 29)
 }
+
+#[test]
+fn test_path() {
+    use itertools::Itertools;
+
+    let input = crate::utils::load_test_file(2022, 12);
+    let map = parse(&input);
+    let path = shortest_path(&map, &get_cost_map(&map));
+    let drawn = MyGrid::cparse(&input).with_path(&path);
+    assert_eq!(path.len(), 32);
+    assert_eq!(
+        drawn.iter_rows().map(|mut row| row.join("")).collect_vec(),
+        vec!["S>><<<<^", "abvv<<^^", "acvvv>^^", "acvv>>>^", "abv>>>>>"]
+    );
+}
@@ -3,34 +3,20 @@ use std::iter::once;
 
 use itertools::Itertools;
 
+use crate::combinators::{delimited, map, number, or, parse_all, separated, PResult};
+use crate::utils::sections;
+
 #[derive(Debug, Clone)]
 enum Entry {
-    Single(u8),
+    Single(u32),
     List(Vec<Entry>),
 }
 
-fn parse_line_recursive(it: &mut impl Iterator<Item=u8>) -> Entry {
-    let mut list = Vec::<Entry>::new();
-    loop {
-        let mut c = it.next().expect("There always should be a char");
-        match c {
-            b',' => {}
-            b'[' => list.push(parse_line_recursive(it)),
-            b']' => return Entry::List(list),
-            b'0'..=b'9' => {
-                let mut val = 0;
-                while c.is_ascii_digit() {
-                    val = val * 10 + c - b'0';
-                    c = it.next().expect("There should always be a char after a digit");
-                }
-                list.push(Entry::Single(val));
-                if c == b']' {
-                    return Entry::List(list);
-                }
-            }
-            _ => panic!("This is an invalid char: '{}'!!", (c as char).escape_debug())
-        }
-    }
+fn entry(input: &str) -> PResult<'_, Entry> {
+    or(
+        map(number(), Entry::Single),
+        map(delimited("[", separated(entry, ","), "]"), Entry::List),
+    )(input)
 }
 
 impl Eq for Entry {}
@@ -93,9 +79,7 @@ struct Pair {
 impl Pair {
     fn parse(lines: &str) -> Self {
         let mut content = lines.lines().map(|line| {
-            let mut it = line.bytes();
-            it.next().expect("Problem consuming the first [");
-            parse_line_recursive(&mut it)
+            parse_all(entry, line).unwrap_or_else(|e| panic!("{}", e))
         }).collect::<Vec<_>>();
         Self {
             right: content.pop().unwrap(),
@@ -110,7 +94,7 @@ impl Pair {
 
 
 fn parse(input: &str) -> impl Iterator<Item=Pair> + '_ {
-    input.split("\n\n").map(|pairs| {
+    sections(input).map(|pairs| {
         Pair::parse(pairs)
     })
 }
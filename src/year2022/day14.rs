@@ -1,12 +1,10 @@
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
-use grid::Grid;
-use itertools::{Itertools, MinMaxResult};
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::utils::{MyGrid, ParseError, Point};
+use crate::utils::{Bounds, MyGrid, ParseError, Point, Segment, SparseGrid};
 
 lazy_static! {
     static ref COORDS_RE: Regex = Regex::new(r"\d+,\d+").unwrap();
@@ -17,37 +15,41 @@ struct Sequence {
     points: Vec<Point>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 enum Content {
+    #[default]
     Air,
     Rock,
     Sand,
 }
 
-#[derive(Debug)]
-struct Bounds {
-    top: isize,
-    bottom: isize,
-    left: isize,
-    right: isize,
+impl Display for Content {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let repr = match self {
+            Content::Air => '.',
+            Content::Rock => '#',
+            Content::Sand => 'o',
+        };
+        write!(f, "{}", repr)
+    }
 }
 
 struct SandPit {
+    /// Dense grid of the rocks, its `(0, 0)` is at the top left corner of `bounds`
     grid: MyGrid<Content>,
+    /// Bounds of the rocks and the source of the sand, anything outside of it falls forever
     bounds: Bounds,
 }
 
-struct SeqIter<'a> {
-    curr: Option<Point>,
-    others: &'a [Point],
-}
+const SOURCE: Point = Point { x: 500, y: 0 };
 
 impl Sequence {
-    fn iter_pos(&self) -> SeqIter {
-        SeqIter {
-            curr: None,
-            others: &self.points,
-        }
+    /// Every position with rock, going through the lines in order
+    fn iter_pos(&self) -> impl Iterator<Item = Point> + '_ {
+        let first = self.points.first().cloned();
+        first.into_iter().chain(self.points.windows(2).flat_map(|w| {
+            Segment::new(w[0].clone(), w[1].clone()).cells().skip(1)
+        }))
     }
 }
 
@@ -60,38 +62,6 @@ impl FromStr for Sequence {
     }
 }
 
-impl<'a> Iterator for SeqIter<'a> {
-    type Item = Point;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.others.len() == 1 && self.curr.as_ref().is_some_and(|x| *x == self.others[0]) {
-            return None;
-        }
-        match &self.curr {
-            None => {
-                self.curr = Some(self.others[0].clone());
-                self.others = &self.others[1..];
-                self.curr.clone()
-            }
-            Some(p) => {
-                if *p == self.others[0] {
-                    self.others = &self.others[1..];
-                }
-                let o = self.others[0].clone();
-                let dx = p.x - o.x;
-                let dy = p.y - o.y;
-                let mut new_curr = None;
-                if dx > 0 { new_curr = Some(p.left()); }
-                if dx < 0 { new_curr = Some(p.right()); }
-                if dy > 0 { new_curr = Some(p.up()); }
-                if dy < 0 { new_curr = Some(p.down()); }
-                self.curr = new_curr.clone();
-                new_curr
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod seq_tests {
     use crate::utils::Point;
@@ -146,57 +116,46 @@ mod seq_tests {
     }
 }
 
-fn get_bounds(inputs: &[Sequence]) -> Bounds {
-    let max_row = inputs.iter().filter_map(|s| s.points.iter().map(|p| p.row()).max()).max().unwrap();
-    let cols = inputs.iter().flat_map(|s| s.points.iter().map(|p| p.col()));
-    match cols.minmax() {
-        MinMaxResult::MinMax(min, max) => Bounds {
-            top: 0,
-            bottom: max_row + 1,
-            left: min,
-            right: max + 1,
-        },
-        _ => panic!("should have a max and a min"),
+impl SandPit {
+    fn add_rocks(grid: &mut SparseGrid<Content>, seq: &Sequence) {
+        seq.iter_pos().for_each(|p| {
+            grid.insert(p, Content::Rock);
+        })
     }
-}
 
-impl SandPit {
-    fn init(bounds: Bounds) -> Self {
-        SandPit {
-            grid: MyGrid(Grid::init((bounds.bottom + 2) as usize,
-                                    (bounds.right + 2) as usize,
-                                    Content::Air)),
-            bounds,
-        }
+    fn out_of_bounds(&self, pos: &Point) -> bool {
+        pos.y > self.bounds.bottom || pos.x < self.bounds.left || pos.x > self.bounds.right
     }
 
-    fn add_rocks(&mut self, seq: &Sequence) {
-        seq.iter_pos().for_each(|p| self.grid[&p] = Content::Rock)
+    /// Position in `grid` of a point of the cave
+    fn local(&self, pos: &Point) -> Point {
+        Point::new(pos.x - self.bounds.left, pos.y - self.bounds.top)
     }
 
-    fn out_of_bounds(&self, pos: &Point) -> bool {
-        pos.y > self.bounds.bottom || pos.x < self.bounds.left || pos.x > self.bounds.right
+    fn get(&self, pos: &Point) -> &Content {
+        self.grid.get(&self.local(pos)).unwrap_or(&Content::Air)
     }
 
     fn drop_sand(&mut self) -> bool {
-        let mut pos = Point::new(500, 0);
-        if self.grid[&pos] == Content::Sand {
+        let mut pos = SOURCE;
+        if *self.get(&pos) == Content::Sand {
             return false;
         }
         loop {
             let down = pos.down();
-            match self.grid[&down] {
+            match self.get(&down) {
                 Content::Air => { pos = down }
                 _ => {
                     let down_left = pos.down_left();
-                    match self.grid[&down_left] {
+                    match self.get(&down_left) {
                         Content::Air => { pos = down_left }
                         _ => {
                             let down_right = pos.down_right();
-                            match self.grid[&down_right] {
+                            match self.get(&down_right) {
                                 Content::Air => { pos = down_right }
                                 _ => {
-                                    self.grid[&pos] = Content::Sand;
+                                    let local = self.local(&pos);
+                                    self.grid[&local] = Content::Sand;
                                     return true;
                                 }
                             }
@@ -221,28 +180,21 @@ impl SandPit {
 
 impl Debug for SandPit {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f)?;
-        for r in self.bounds.top..self.bounds.bottom {
-            write!(f, "{} ", r)?;
-            for c in self.bounds.left..self.bounds.right {
-                write!(f, "{}", match self.grid.0[(r as usize, c as usize)] {
-                    Content::Air => '.',
-                    Content::Rock => '#',
-                    Content::Sand => '0',
-                })?
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{:?}", self.grid)
     }
 }
 
 impl From<Vec<Sequence>> for SandPit {
     fn from(seqs: Vec<Sequence>) -> Self {
-        let bounds = get_bounds(&seqs);
-        let mut res = Self::init(bounds);
-        seqs.iter().for_each(|seq| res.add_rocks(seq));
-        res
+        let mut grid = SparseGrid::new();
+        seqs.iter().for_each(|seq| Self::add_rocks(&mut grid, seq));
+        grid.insert(SOURCE, Content::Air);
+        let bounds = grid.bounds().unwrap().clone();
+        // The sparse grid is only to find the size, the sand falls a lot faster on a dense one
+        SandPit {
+            grid: grid.to_grid(),
+            bounds,
+        }
     }
 }
 
@@ -259,12 +211,12 @@ pub fn part1(input: &str) -> usize {
 
 pub fn part2(input: &str) -> usize {
     let mut seqs = parse(input);
-    let bounds = get_bounds(&seqs);
-    // Add the bottom line
+    let bottom = seqs.iter().flat_map(|s| s.points.iter().map(|p| p.row())).max().unwrap();
+    // Add the bottom line, wide enough to hold the whole pyramid of sand
     seqs.push(Sequence {
         points: vec![
-            Point::new(0, bounds.bottom + 1),
-            Point::new(bounds.right + bounds.bottom, bounds.bottom + 1),
+            Point::new(500 - bottom - 3, bottom + 2),
+            Point::new(500 + bottom + 3, bottom + 2),
         ]
     });
     let mut pit = SandPit::from(seqs);
@@ -1,39 +1,40 @@
-use std::ops::RangeInclusive;
-
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
 
-type IncRange = RangeInclusive<i64>;
+use crate::utils::IntervalSet;
+
+type Sections = IntervalSet<i64>;
 
 lazy_static! {
     static ref RE: Regex = Regex::new(r"(\d+)-(\d+),(\d+)-(\d+)").unwrap();
 }
 
-fn parse_line(line: &str) -> (IncRange, IncRange) {
+fn parse_line(line: &str) -> (Sections, Sections) {
     fn to_i64(groups: &Captures, i: usize) -> i64 {
         groups.get(i).unwrap().as_str().parse().unwrap()
     }
     let groups = RE.captures(line).unwrap();
-    let first = IncRange::new(to_i64(&groups, 1), to_i64(&groups, 2));
-    let second = IncRange::new(to_i64(&groups, 3), to_i64(&groups, 4));
+    let first = Sections::from_range(to_i64(&groups, 1)..=to_i64(&groups, 2));
+    let second = Sections::from_range(to_i64(&groups, 3)..=to_i64(&groups, 4));
     (first, second)
 }
 
-fn parse(input: &str) -> impl Iterator<Item=(IncRange, IncRange)> + '_ {
+fn parse(input: &str) -> impl Iterator<Item=(Sections, Sections)> + '_ {
     input.lines().map(parse_line)
 }
 
-fn is_contained(pair: &(IncRange, IncRange)) -> bool {
+fn is_contained(pair: &(Sections, Sections)) -> bool {
     let (a, b) = pair;
-    (a.contains(b.start()) && a.contains(b.end())) || (b.contains(a.start()) && b.contains(a.end()))
+    let common = a.intersection(b);
+    common == *a || common == *b
 }
 
-fn overlaps(pair: &(IncRange, IncRange)) -> bool {
+fn overlaps(pair: &(Sections, Sections)) -> bool {
     let (a, b) = pair;
-    a.contains(b.start()) || b.contains(a.start())
+    !a.intersection(b).is_empty()
 }
 
-fn solve(input: &str, f: fn(&(IncRange, IncRange)) -> bool) -> i64 {
+fn solve(input: &str, f: fn(&(Sections, Sections)) -> bool) -> i64 {
     parse(input).filter(f).count() as i64
 }
 
@@ -1,20 +1,18 @@
 use std::collections::VecDeque;
 
 use itertools::enumerate;
-use lazy_static::lazy_static;
-use regex::Regex;
-
-lazy_static! {
-    static ref MOVE_RE: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-}
+use crate::regex_struct;
 
 type Stacks = Vec<VecDeque<char>>;
 
-#[derive(Debug)]
-struct Move {
-    from: usize,
-    to: usize,
-    n: usize,
+regex_struct! {
+    #[regex(r"move (?P<n>\d+) from (?P<from>\d+) to (?P<to>\d+)")]
+    #[derive(Debug)]
+    struct Move {
+        from: usize,
+        to: usize,
+        n: usize,
+    }
 }
 
 #[derive(Debug)]
@@ -37,20 +35,7 @@ fn parse_stack(raw: Vec<&str>) -> Option<Stacks> {
 }
 
 fn parse_moves(raw: Vec<&str>) -> Vec<Move> {
-    fn parse_move(line: &str) -> Option<Move> {
-        MOVE_RE.captures(line).and_then(|cap| {
-            let groups = (cap.get(1), cap.get(2), cap.get(3));
-            match groups {
-                (Some(n), Some(from), Some(to)) => Some(Move {
-                    n: n.as_str().parse().ok()?,
-                    from: from.as_str().parse().ok()?,
-                    to: to.as_str().parse().ok()?,
-                }),
-                _ => None
-            }
-        })
-    }
-    raw.iter().filter_map(|line| parse_move(line)).collect()
+    raw.iter().map(|line| line.parse().unwrap()).collect()
 }
 
 fn parse(input: &str) -> (Crates, Vec<Move>) {
@@ -1,28 +1,20 @@
-use std::collections::HashSet;
-
-use lazy_static::lazy_static;
-use regex::Regex;
-
+use crate::regex_struct;
 use crate::utils::Direction;
 use crate::utils::Direction::*;
-use crate::utils::Point;
+use crate::utils::{Point, SparseGrid};
 
-lazy_static! {
-    static ref MOVE_RE: Regex = Regex::new(r"([UDLR]) (\d+)").unwrap();
+regex_struct! {
+    #[regex(r"(?P<dir>[UDLR]) (?P<steps>\d+)")]
+    struct Move {
+        dir: Direction,
+        steps: u8,
+    }
 }
 
 fn parse(input: &str) -> impl Iterator<Item = (Direction, u8)> + '_ {
-    input.lines().filter_map(|line| {
-        MOVE_RE.captures(line).and_then(|cap| {
-            let steps = cap.get(2)?.as_str().parse().ok()?;
-            match cap.get(1)?.as_str() {
-                "U" => Some((Up, steps)),
-                "D" => Some((Down, steps)),
-                "L" => Some((Left, steps)),
-                "R" => Some((Right, steps)),
-                _ => None,
-            }
-        })
+    input.lines().map(|line| {
+        let Move { dir, steps } = line.parse().unwrap();
+        (dir, steps)
     })
 }
 
@@ -46,7 +38,7 @@ fn move_node(new: &Point, tail: &Point) -> Point {
 
 // could be improved by not creating new Vec every iteration and do it in place,
 // but this is fast enough
-fn update_propagation(rope: &Vec<Point>, new_head: Point) -> Vec<Point> {
+fn update_propagation(rope: &[Point], new_head: Point) -> Vec<Point> {
     let mut res = vec![Point::ZERO; 10];
     res[0] = new_head;
     for i in 1..rope.len() {
@@ -57,7 +49,7 @@ fn update_propagation(rope: &Vec<Point>, new_head: Point) -> Vec<Point> {
 
 pub fn solve(input: &str, rope_size: usize) -> u64 {
     let mut rope = vec![Point::ZERO; rope_size];
-    let mut all_tail_positions = HashSet::new();
+    let mut tail_positions = SparseGrid::with_default('.');
     parse(input).for_each(|(dir, steps)| {
         for _ in 0..steps {
             let new_head = match dir {
@@ -67,10 +59,10 @@ pub fn solve(input: &str, rope_size: usize) -> u64 {
                 Right => rope[0].right(),
             };
             rope = update_propagation(&rope, new_head);
-            all_tail_positions.insert(rope[rope_size - 1].clone());
+            tail_positions.insert(rope[rope_size - 1].clone(), '#');
         }
     });
-    all_tail_positions.len() as u64
+    tail_positions.len() as u64
 }
 
 pub fn part1(input: &str) -> u64 {
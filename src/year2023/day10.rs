@@ -1,7 +1,4 @@
-use std::collections::{HashSet, HashMap};
-
-use itertools::Itertools;
-
+use crate::geometry::Polygon;
 use crate::utils::{Direction, MyGrid, Point};
 
 static EMPTY_CHAR: char = '.';
@@ -91,54 +88,21 @@ fn find_connected(grid: &MyGrid<char>, point: &Point) -> ((Point, Point), char)
     (points, new_char)
 }
 
-fn get_line_points(grid: &MyGrid<char>, point: &Point) -> HashSet<Point> {
+/// The points of the loop that goes through `point`, in order, starting with `point`
+fn get_line_points(grid: &MyGrid<char>, point: &Point) -> Vec<Point> {
     let mut prev_p1 = point.clone();
     let ((mut p1, _), _) = find_connected(grid, point);
-    let mut res = HashSet::new();
+    let mut res = vec![];
     while p1 != *point {
         let n = next(grid, &p1, &prev_p1);
-        res.insert(prev_p1);
+        res.push(prev_p1);
         prev_p1 = p1;
         p1 = n;
     }
-    res.insert(prev_p1);
+    res.push(prev_p1);
     res
 }
 
-fn clean_grid(grid: &mut MyGrid<char>, points: &HashSet<Point>) {
-    for r in 0..grid.size().0 {
-        for c in 0..grid.size().1 {
-            let p = Point::from((r, c));
-            if !points.contains(&p) {
-                grid[(r, c)] = EMPTY_CHAR
-            }
-        }
-    }
-}
-
-fn is_inside(grid: &MyGrid<char>, point: (usize, usize)) -> bool {
-    fn val(counts: &HashMap<char, usize>, val: char) -> isize {
-        *counts.get(&val).unwrap_or(&0) as isize
-    }
-    if grid[point] != EMPTY_CHAR {
-        return false;
-    }
-    let tmp: String = grid
-        .to_edge(&Point::from(point), Direction::Up)
-        .filter(|x| x != &&EMPTY_CHAR)
-        .filter(|x| x != &&'|')
-        .collect();
-    if tmp.is_empty() {
-        return false;
-    }
-    let tmp = tmp.replace("LF", "")
-        .replace("J7", "")
-        .replace("L7", "-")
-        .replace("JF", "-");
-    assert!(tmp.chars().all(|x| x == '-'));
-    tmp.len() % 2 == 1
-}
-
 #[test]
 fn test_fns() {
     use indoc::indoc;
@@ -161,26 +125,20 @@ fn test_fns() {
     assert_eq!(find_connected(&grid, &p(3, 2)), ((p(3, 1), p(3, 3)), '-'));
 }
 
+fn parse(input: &str) -> (MyGrid<char>, Point) {
+    let (grid, markers) = MyGrid::parse_with_markers(input, |x| x as char, &[('S', EMPTY_CHAR)]);
+    let start = markers[&'S'][0].clone();
+    (grid, start)
+}
+
 pub fn part1(input: &str) -> usize {
-    let grid = MyGrid::cparse(input);
-    let start = Point::from(grid.find('S').unwrap());
+    let (grid, start) = parse(input);
     get_line_points(&grid, &start).len() / 2
 }
 
 pub fn part2(input: &str) -> usize {
-    let mut grid = MyGrid::cparse(input);
-    let start = Point::from(grid.find('S').unwrap());
-    let points = get_line_points(&grid, &start);
-    grid[&start] = find_connected(&grid, &start).1;
-    clean_grid(&mut grid, &points);
-    let points = grid.indexed_iter()
-        .filter(|(p, _)| is_inside(&grid, *p))
-        .map(|(p, _)| p)
-        .collect_vec();
-    for p in &points {
-        grid[*p] = 'X';
-    }
-    points.len()
+    let (grid, start) = parse(input);
+    Polygon::from_path(&get_line_points(&grid, &start)).interior_points()
 }
 
 #[test]
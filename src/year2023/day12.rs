@@ -1,5 +1,5 @@
 use core::panic;
-use std::{iter::repeat, collections::HashMap};
+use std::{iter::repeat_n, collections::HashMap};
 
 use itertools::Itertools;
 
@@ -19,12 +19,11 @@ impl HotSpring {
     fn parse(line: &str, times: usize) -> Self {
         let (state, rec) = line.trim().split_once(' ').unwrap();
 
-        let record: Vec<u8> = repeat(rec.split(',').map(|x| x.parse().unwrap()))
-            .take(times)
+        let record: Vec<u8> = repeat_n(rec.split(',').map(|x| x.parse().unwrap()), times)
             .flatten()
             .collect();
         Self {
-            current: format!("{}.", repeat(state).take(times).join("?")),
+            current: format!("{}.", repeat_n(state, times).join("?")),
             record,
         }
     }
@@ -1,11 +1,13 @@
-use crate::utils::MyGrid;
+use crate::utils::{sections, GridView, MyGrid};
+
+type View<'a> = GridView<&'a MyGrid<u8>>;
 
 fn parse(input: &str) -> impl Iterator<Item = MyGrid<u8>> + '_ {
-    input.split("\n\n").map(|entries| MyGrid::parse(entries, |x| x))
+    sections(input).map(|entries| MyGrid::parse(entries, |x| x))
 }
 
 /// row 0 means that the relfection point is between row 0 and 1
-fn is_reflection_part1(grid: &MyGrid<u8>, row: usize) -> bool {
+fn is_reflection_part1(grid: &View, row: usize) -> bool {
     let mut start: isize = row as isize;
     let mut end = row + 1;
 
@@ -23,7 +25,7 @@ fn is_reflection_part1(grid: &MyGrid<u8>, row: usize) -> bool {
 }
 
 /// row 0 means that the relfection point is between row 0 and 1
-fn is_reflection_part2(grid: &MyGrid<u8>, row: usize) -> bool {
+fn is_reflection_part2(grid: &View, row: usize) -> bool {
     let mut start: isize = row as isize;
     let mut end = row + 1;
     let mut mutation = 1_isize;
@@ -45,9 +47,9 @@ fn is_reflection_part2(grid: &MyGrid<u8>, row: usize) -> bool {
     mutation == 0
 }
 
-fn find_reflection(grid: &mut MyGrid<u8>, is_reflection: fn(grid: &MyGrid<u8>, row: usize) -> bool) -> usize {
+fn find_reflection(grid: &MyGrid<u8>, is_reflection: fn(grid: &View, row: usize) -> bool) -> usize {
 
-    fn get_reflection_pos(grid: &MyGrid<u8>, is_reflection: fn(grid: &MyGrid<u8>, row: usize) -> bool) -> usize {
+    fn get_reflection_pos(grid: &View, is_reflection: fn(grid: &View, row: usize) -> bool) -> usize {
         for row in 0..grid.rows() - 1 {
             if is_reflection(grid, row) {
                 return row + 1;
@@ -55,12 +57,11 @@ fn find_reflection(grid: &mut MyGrid<u8>, is_reflection: fn(grid: &MyGrid<u8>, r
         }
         0
     }
-    let res = get_reflection_pos(grid, is_reflection) * 100;
+    let res = get_reflection_pos(&grid.view(), is_reflection) * 100;
     if res > 0 {
         return res;
     }
-    grid.transpose();
-    let res = get_reflection_pos(grid, is_reflection);
+    let res = get_reflection_pos(&grid.view().transpose(), is_reflection);
     assert!(res != 0, "There should be a reflection");
     res
 }
@@ -68,7 +69,7 @@ fn find_reflection(grid: &mut MyGrid<u8>, is_reflection: fn(grid: &MyGrid<u8>, r
 #[test]
 fn test_reflect() {
     use indoc::indoc;
-    let mut grid = MyGrid::parse(indoc! {"
+    let grid = MyGrid::parse(indoc! {"
         #...##..#
         #....#..#
         ..##..###
@@ -77,16 +78,16 @@ fn test_reflect() {
         ..##..###
         #....#..#
     "}, |x| x);
-    assert_eq!(find_reflection(&mut grid, is_reflection_part1), 400);
-    assert_eq!(find_reflection(&mut grid, is_reflection_part2), 100);
+    assert_eq!(find_reflection(&grid, is_reflection_part1), 400);
+    assert_eq!(find_reflection(&grid, is_reflection_part2), 100);
 }
 
 pub fn part1(input: &str) -> usize {
-    parse(input).map(|mut grid| find_reflection(&mut grid, is_reflection_part1)).sum()
+    parse(input).map(|grid| find_reflection(&grid, is_reflection_part1)).sum()
 }
 
 pub fn part2(input: &str) -> usize {
-    parse(input).map(|mut grid| find_reflection(&mut grid, is_reflection_part2)).sum()
+    parse(input).map(|grid| find_reflection(&grid, is_reflection_part2)).sum()
 }
 
 #[test]
@@ -1,10 +1,7 @@
-use std::collections::{hash_map::Entry, HashMap};
-
-use grid::Grid;
-use itertools::Itertools;
-
+use crate::cycles::nth_state;
 use crate::utils::{Direction, MyGrid};
 
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct Dish {
     data: MyGrid<u8>,
 }
@@ -48,40 +45,19 @@ impl Dish {
     }
 
     fn tilt(&mut self, dir: Direction) {
-        let mut new_grid = MyGrid(Grid::<u8>::new(0, 0));
-        match dir {
-            Direction::Up => {
-                for col in self.data.iter_cols() {
-                    new_grid.push_row(move_rocks(col.cloned()))
-                }
-                new_grid.transpose();
-            }
-            Direction::Down => {
-                self.data.rotate_half();
-                for col in self.data.iter_cols() {
-                    new_grid.push_row(move_rocks(col.cloned()))
-                }
-                new_grid.transpose();
-                new_grid.rotate_half();
-            }
-            Direction::Left => {
-                self.data.rotate_right();
-                for col in self.data.iter_cols() {
-                    new_grid.push_row(move_rocks(col.cloned()))
-                }
-                new_grid.transpose();
-                new_grid.rotate_left();
-            }
-            Direction::Right => {
-                self.data.rotate_left();
-                for col in self.data.iter_cols() {
-                    new_grid.push_row(move_rocks(col.cloned()))
-                }
-                new_grid.transpose();
-                new_grid.rotate_right();
+        // Look at the dish so that the rocks always roll up
+        let mut view = match dir {
+            Direction::Up => self.data.view_mut(),
+            Direction::Down => self.data.view_mut().flip_rows(),
+            Direction::Left => self.data.view_mut().transpose(),
+            Direction::Right => self.data.view_mut().transpose().flip_rows(),
+        };
+        for c in 0..view.cols() {
+            let col = move_rocks(view.iter_col(c).copied());
+            for (r, x) in col.into_iter().enumerate() {
+                view[(r, c)] = x;
             }
         }
-        self.data = new_grid;
     }
 
     fn load(&self) -> usize {
@@ -92,14 +68,15 @@ impl Dish {
             .sum()
     }
 
-    fn repr(&self) -> Vec<u128> {
-        self.data
-            .iter_rows()
-            .map(|row| {
-                let bin_str = &row.map(|x| if x == &b'O' { 0 } else { 1 }).join("");
-                u128::from_str_radix(bin_str, 2).unwrap()
-            })
-            .collect_vec()
+    /// Tilts north, then west, then south, then east
+    fn spin_cycle(&self) -> Dish {
+        let mut res = self.clone();
+        let mut dir = Direction::Up;
+        for _ in 0..4 {
+            res.tilt(dir);
+            dir = dir.rotate(crate::utils::Rotation::AntiClockwise);
+        }
+        res
     }
 }
 
@@ -110,29 +87,7 @@ pub fn part1(input: &str) -> usize {
 }
 
 pub fn part2(input: &str) -> usize {
-    let mut dish = Dish::parse(input);
-    let mut dir = Direction::Up;
-    let mut encounterd: HashMap<Vec<u128>, usize> = HashMap::new();
-    let mut saved = vec![0];
-    let mut i = 1;
-    let mut cycles = 0;
-    let last = loop {
-        dish.tilt(dir);
-        dir = dir.rotate(crate::utils::Rotation::AntiClockwise);
-        if i % 4 == 0 {
-            cycles += 1;
-            saved.push(dish.load());
-            let repr = dish.repr();
-            if let Entry::Vacant(e) = encounterd.entry(repr.clone()) {
-                e.insert(cycles);
-            } else {
-                break *encounterd.get(&repr).unwrap();
-            }
-        }
-        i += 1;
-    };
-    let cycle_len = cycles - last;
-    saved[((1000000000 - last) % cycle_len) + last]
+    nth_state(Dish::parse(input), Dish::spin_cycle, 1000000000).load()
 }
 
 #[test]
@@ -1,138 +1,159 @@
-use std::cmp::{max, min};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
 
 use itertools::Itertools;
 
-#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
-struct Range {
+use crate::utils::{ints, sections, IntervalSet};
+
+/// From `start` up to the start of the next piece, the numbers are mapped to `destination` onwards
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Piece {
     start: u64,
-    end: u64, // inclusive
+    destination: u64,
 }
 
-impl Range {
-    // (before this, in this, after this)
-    fn intersect(&self, other: &Range) -> Option<Range> {
-        // in
-        if other.start <= self.end && other.end >= self.start {
-            return Some(Range {
-                start: max(self.start, other.start),
-                end: min(self.end, other.end),
-            });
-        }
-        None
+impl Piece {
+    fn map(&self, val: u64) -> u64 {
+        self.destination + (val - self.start)
     }
 }
 
-#[derive(Debug)]
-struct MapPart {
-    source: u64,
-    destination: u64,
-    span: u64,
-    range: Range,
+/// Function defined for every `u64`, made of increasing pieces. The numbers not covered by
+/// any line of the almanac are mapped to themselves.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct PiecewiseMap {
+    /// Sorted by start, and the first one starts at 0
+    pieces: Vec<Piece>,
 }
 
-impl MapPart {
-    fn parse(line: &str) -> MapPart {
-        let (destination, source, range) = line
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect_tuple()
-            .unwrap();
-        MapPart {
-            source,
-            destination,
-            span: range,
-            range: Range {
+impl PiecewiseMap {
+    fn identity() -> Self {
+        PiecewiseMap {
+            pieces: vec![Piece {
+                start: 0,
+                destination: 0,
+            }],
+        }
+    }
+
+    fn parse(input: &str) -> Self {
+        let mut pieces = vec![];
+        // `None` when the previous line goes up to u64::MAX
+        let mut next_start = Some(0);
+        for (destination, source, span) in input
+            .lines()
+            .skip(1)
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|n| n.parse::<u64>().unwrap())
+                    .collect_tuple()
+                    .unwrap()
+            })
+            .filter(|(_, _, span)| *span > 0)
+            .sorted_by_key(|(_, source, _)| *source)
+        {
+            if let Some(start) = next_start.filter(|start| *start < source) {
+                pieces.push(Piece {
+                    start,
+                    destination: start,
+                });
+            }
+            pieces.push(Piece {
                 start: source,
-                end: source + range - 1,
-            },
+                destination,
+            });
+            next_start = source.checked_add(span);
         }
+        if let Some(start) = next_start {
+            pieces.push(Piece {
+                start,
+                destination: start,
+            });
+        }
+        Self::simplified(pieces)
     }
 
-    fn id_map(start: u64, end: u64) -> Self {
-        assert!(start <= end, "<{}, {}>", start, end);
-        MapPart {
-            source: start,
-            destination: start,
-            span: end - start + 1,
-            range: Range { start, end },
+    /// Joins the pieces that just continue the previous one
+    fn simplified(pieces: Vec<Piece>) -> Self {
+        let mut res: Vec<Piece> = vec![];
+        for piece in pieces {
+            match res.last() {
+                Some(last)
+                    if last.map(piece.start - 1).checked_add(1) == Some(piece.destination) => {}
+                _ => res.push(piece),
+            }
         }
+        PiecewiseMap { pieces: res }
     }
 
-    fn contains(&self, val: u64) -> bool {
-        val >= self.source && val < self.source + self.span
+    fn index_of(&self, val: u64) -> usize {
+        self.pieces.partition_point(|piece| piece.start <= val) - 1
     }
 
     fn map(&self, val: u64) -> u64 {
-        (self.destination as i64 - self.source as i64 + val as i64) as u64
+        self.pieces[self.index_of(val)].map(val)
     }
 
-    fn map_range(&self, other: &Range) -> Option<Range> {
-        self.range.intersect(other).map(|range| Range {
-            start: self.map(range.start),
-            end: self.map(range.end),
+    /// Each piece with the range of numbers it covers
+    fn iter(&self) -> impl Iterator<Item = (RangeInclusive<u64>, &Piece)> {
+        self.pieces.iter().enumerate().map(|(i, piece)| {
+            let end = match self.pieces.get(i + 1) {
+                Some(next) => next.start - 1,
+                None => u64::MAX,
+            };
+            (piece.start..=end, piece)
         })
     }
-}
-
-#[derive(Debug)]
-struct CompleteMap<'a> {
-    info: &'a str,
-    parts: Vec<MapPart>,
-}
 
-impl<'a> CompleteMap<'a> {
-    fn parse(input: &'a str) -> Self {
-        let mut lines = input.lines();
-        let info = lines.next().unwrap();
-        let temp_parts = lines
-            .map(MapPart::parse)
-            .sorted_by(|a, b| Ord::cmp(&a.source, &b.source))
-            .collect_vec();
-        let mut parts = vec![];
-        let mut start = 0;
-        let mut end = 0;
-        for part in temp_parts {
-            let MapPart { source, span, .. } = part;
-            if source > 0 {
-                end = source - 1;
+    /// Map that does `self` and then `next`
+    fn then(&self, next: &PiecewiseMap) -> Self {
+        let mut pieces = vec![];
+        for (range, piece) in self.iter() {
+            let image_start = piece.destination;
+            let image_end = piece.map(*range.end());
+            // Split the piece where its image goes into another piece of `next`
+            let mut from = image_start;
+            loop {
+                let idx = next.index_of(from);
+                pieces.push(Piece {
+                    start: range.start() + (from - image_start),
+                    destination: next.pieces[idx].map(from),
+                });
+                match next.pieces.get(idx + 1) {
+                    Some(other) if other.start <= image_end => from = other.start,
+                    _ => break,
+                }
             }
-            if source > 0 && start <= end {
-                parts.push(MapPart::id_map(start, end));
-            }
-            parts.push(part);
-            start = source + span;
-        }
-        if start - 1 != u32::MAX as u64 {
-            parts.push(MapPart::id_map(start, u32::MAX as u64));
         }
-        //my_dbg!(parts.iter().map(|x| x.range.clone()).collect_vec());
-        CompleteMap { info, parts }
-    }
-
-    fn map(&self, val: u64) -> u64 {
-        self.parts
-            .iter()
-            .find(|m| m.contains(val))
-            .map(|m| m.map(val))
-            .unwrap_or(val)
+        Self::simplified(pieces)
     }
 
-    // can be improved with a binary search instead of going over all the list
-    fn map_range(&self, range: &Range) -> Vec<Range> {
-        self.parts
-            .iter()
-            .filter_map(|part| part.map_range(range))
-            .sorted()
+    /// All the numbers that the numbers of `set` are mapped to
+    fn image(&self, set: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.iter()
+            .flat_map(|(range, piece)| {
+                set.intersection(&IntervalSet::from_range(range))
+                    .iter()
+                    .map(|r| piece.map(*r.start())..=piece.map(*r.end()))
+                    .collect_vec()
+            })
             .collect()
     }
+}
 
-    fn map_ranges(&self, ranges: &Vec<Range>) -> Vec<Range> {
-        let mut res = vec![];
-        for range in ranges {
-            res.extend(self.map_range(range))
+impl Display for PiecewiseMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (range, piece) in self.iter() {
+            writeln!(
+                f,
+                "{}..={} -> {}..={}",
+                range.start(),
+                range.end(),
+                piece.destination,
+                piece.map(*range.end())
+            )?;
         }
-        res.sort();
-        res
+        Ok(())
     }
 }
 
@@ -140,37 +161,22 @@ impl<'a> CompleteMap<'a> {
 fn test_inter() {
     use indoc::indoc;
 
-    fn cm(src: u64, dest: u64, span: u64) -> MapPart {
-        MapPart {
-            source: src,
-            destination: dest,
-            span,
-            range: cr(src, src + span - 1),
-        }
-    }
-    fn cr(start: u64, end: u64) -> Range {
-        Range { start, end }
+    fn set(ranges: &[RangeInclusive<u64>]) -> IntervalSet<u64> {
+        ranges.iter().cloned().collect()
     }
-    fn sr(start: u64, end: u64) -> Option<Range> {
-        Some(cr(start, end))
-    }
-    let base = cr(5, 10);
-    assert_eq!(base.intersect(&cr(1, 3)), None);
-    assert_eq!(base.intersect(&cr(1, 4)), None);
-    assert_eq!(base.intersect(&cr(1, 5)), sr(5, 5));
-    assert_eq!(base.intersect(&cr(4, 10)), sr(5, 10));
-    assert_eq!(base.intersect(&cr(10, 11)), sr(10, 10));
-    assert_eq!(base.intersect(&cr(4, 15)), sr(5, 10));
-    assert_eq!(base.intersect(&cr(10, 15)), sr(10, 10));
-    assert_eq!(base.intersect(&cr(20, 25)), None);
 
-    let map_part = cm(10, 20, 15);
-    assert_eq!(map_part.map_range(&cr(0, 9)), None);
-    assert_eq!(map_part.map_range(&cr(0, 10)), sr(20, 20));
-    assert_eq!(map_part.map_range(&cr(0, 20)), sr(20, 30));
-    assert_eq!(map_part.map_range(&cr(0, 30)), sr(20, 34));
-    assert_eq!(map_part.map_range(&cr(12, 20)), sr(22, 30));
-    assert_eq!(map_part.map_range(&cr(25, 40)), None);
+    let map_part = PiecewiseMap::parse(indoc! {"
+        x-to-y map:
+        20 10 15
+    "});
+    assert_eq!(map_part.map(9), 9);
+    assert_eq!(map_part.map(10), 20);
+    assert_eq!(map_part.map(24), 34);
+    assert_eq!(map_part.map(25), 25);
+    assert_eq!(map_part.image(&set(&[0..=9])), set(&[0..=9]));
+    assert_eq!(map_part.image(&set(&[12..=20])), set(&[22..=30]));
+    assert_eq!(map_part.image(&set(&[0..=30])), set(&[0..=9, 20..=34]));
+    assert_eq!(map_part.image(&set(&[25..=40])), set(&[25..=40]));
 
     /*
     seed  soil
@@ -188,99 +194,86 @@ fn test_inter() {
     99    51
      */
     // dest src range
-    let full_map = CompleteMap::parse(indoc! {"
+    let full_map = PiecewiseMap::parse(indoc! {"
         seed-to-soil map:
         50 98 2
         52 50 48
     "});
-    assert_eq!(full_map.map_range(&cr(0, 30)), vec![cr(0, 30)]);
-    assert_eq!(full_map.map_range(&cr(0, 60)), vec![cr(0, 49), cr(52, 62)]);
-    assert_eq!(full_map.map_range(&cr(0, 97)), vec![cr(0, 49), cr(52, 99)]);
+    assert_eq!(full_map.map(49), 49);
+    assert_eq!(full_map.map(50), 52);
+    assert_eq!(full_map.map(98), 50);
+    assert_eq!(full_map.map(u64::MAX), u64::MAX);
+    assert_eq!(full_map.image(&set(&[0..=30])), set(&[0..=30]));
+    assert_eq!(full_map.image(&set(&[0..=60])), set(&[0..=49, 52..=62]));
+    assert_eq!(full_map.image(&set(&[0..=97])), set(&[0..=49, 52..=99]));
+    assert_eq!(full_map.image(&set(&[0..=99])), set(&[0..=99]));
+    assert_eq!(full_map.image(&set(&[0..=200])), set(&[0..=200]));
     assert_eq!(
-        full_map.map_range(&cr(0, 99)),
-        vec![cr(0, 49), cr(50, 51), cr(52, 99),]
+        full_map.image(&set(&[0..=30, 31..=60])),
+        set(&[0..=49, 52..=62])
     );
+    assert_eq!(full_map.image(&set(&[60..=98])), set(&[50..=50, 62..=99]));
     assert_eq!(
-        full_map.map_range(&cr(0, 200)),
-        vec![cr(0, 49), cr(50, 51), cr(52, 99), cr(100, 200)]
+        full_map.to_string(),
+        indoc! {"
+            0..=49 -> 0..=49
+            50..=97 -> 52..=99
+            98..=99 -> 50..=51
+            100..=18446744073709551615 -> 100..=18446744073709551615
+        "}
     );
 
-    // map ranges
-    assert_eq!(full_map.map_ranges(&vec![cr(0, 30)]), vec![cr(0, 30)]);
-    assert_eq!(
-        full_map.map_ranges(&vec![cr(0, 14), cr(15, 30)]),
-        vec![cr(0, 14), cr(15, 30)]
-    );
-    assert_eq!(
-        full_map.map_ranges(&vec![cr(0, 30), cr(31, 60)]),
-        vec![cr(0, 30), cr(31, 49), cr(52, 62)]
-    );
-    assert_eq!(
-        full_map.map_ranges(&vec![cr(0, 49), cr(50, 97), cr(98, 99)]),
-        vec![cr(0, 49), cr(50, 51), cr(52, 99)]
-    );
-    assert_eq!(
-        full_map.map_ranges(&vec![cr(60, 98)]),
-        vec![cr(50, 50), cr(62, 99)]
-    );
+    // Going back and forth cancels out
+    let back = PiecewiseMap::parse(indoc! {"
+        soil-to-seed map:
+        98 50 2
+        50 52 48
+    "});
+    assert_eq!(full_map.then(&back), PiecewiseMap::identity());
+    let composed = full_map.then(&map_part);
+    for seed in [0, 10, 22, 49, 50, 60, 97, 98, 99, 100] {
+        assert_eq!(composed.map(seed), map_part.map(full_map.map(seed)));
+    }
 }
 
 #[derive(Debug)]
-struct Alamanac<'a> {
+struct Alamanac {
     start: Vec<u64>,
-    maps: Vec<CompleteMap<'a>>,
+    maps: Vec<PiecewiseMap>,
 }
 
-impl<'a> Alamanac<'a> {
-    fn parse(input: &'a str) -> Self {
-        let mut blocks = input.split("\n\n");
-        let entries = blocks
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .skip(1)
-            .map(|s| s.parse().unwrap())
-            .collect_vec();
+impl Alamanac {
+    fn parse(input: &str) -> Self {
+        let mut blocks = sections(input);
         Alamanac {
-            start: entries,
-            maps: blocks.map(CompleteMap::parse).collect(),
+            start: ints(blocks.next().unwrap()).unwrap(),
+            maps: blocks.map(PiecewiseMap::parse).collect(),
         }
     }
-}
 
-fn map_numbers(ns: impl Iterator<Item = u64>, maps: Vec<CompleteMap>) -> u64 {
-    let mut min = u64::MAX;
-    for seed in ns {
-        let mut start = seed;
-        for map in &maps {
-            start = map.map(start)
-        }
-        if start < min {
-            min = start;
-        }
+    /// All the maps combined into one
+    fn seed_to_location(&self) -> PiecewiseMap {
+        self.maps
+            .iter()
+            .fold(PiecewiseMap::identity(), |acc, map| acc.then(map))
     }
-    min
 }
 
 pub fn part1(input: &str) -> u64 {
     let almanac = Alamanac::parse(input);
-    map_numbers(almanac.start.into_iter(), almanac.maps)
+    let map = almanac.seed_to_location();
+    almanac
+        .start
+        .iter()
+        .map(|seed| map.map(*seed))
+        .min()
+        .unwrap()
 }
 
 pub fn part2(input: &str) -> u64 {
     let almanac = Alamanac::parse(input);
-    let mut ranges = almanac
-        .start
-        .chunks(2)
-        .map(|v| Range {
-            start: v[0],
-            end: v[0] + v[1],
-        })
-        .collect_vec();
-    for map in almanac.maps {
-        ranges = map.map_ranges(&ranges);
-    }
-    ranges[0].start
+    let seeds: IntervalSet<u64> = almanac.start.chunks(2).map(|v| v[0]..v[0] + v[1]).collect();
+    almanac.seed_to_location().image(&seeds).min().unwrap()
 }
 
 #[test]
@@ -46,14 +46,14 @@ fn parse_part1(input: &str) -> Vec<Race> {
         .lines()
         .map(|line| line.split_whitespace().skip(1).collect_vec())
         .collect_vec();
-    let mut res = vec![];
-    for i in 0..tmp[0].len() {
-        res.push(Race {
-            time: tmp[0][i].parse().unwrap(),
-            dist: tmp[1][i].parse().unwrap(),
+    tmp[0]
+        .iter()
+        .zip(&tmp[1])
+        .map(|(time, dist)| Race {
+            time: time.parse().unwrap(),
+            dist: dist.parse().unwrap(),
         })
-    }
-    res
+        .collect()
 }
 
 fn parse_part2(input: &str) -> Race {
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
+use std::iter::successors;
 
 use itertools::Itertools;
 use num::integer::lcm;
 
+use crate::cycles::find_cycle;
+use crate::math::{crt, Overflow};
+
 #[derive(Debug)]
 enum Dir {
     L,
@@ -42,7 +46,7 @@ impl<'a> Network<'a> {
         Self { steps, nodes }
     }
 
-    fn next(&self, curr: &str, i: usize) -> &str {
+    fn next(&self, curr: &str, i: usize) -> &'a str {
         let dir = &self.steps[i % self.steps.len()];
         let (l, r) = self.nodes.get(curr).unwrap();
         match dir {
@@ -66,13 +70,111 @@ pub fn part1(input: &str) -> usize {
     Network::parse(input).cycle_len("AAA", |x| x == "ZZZ")
 }
 
-pub fn part2(input: &str) -> usize {
+/// How the walk from a node goes on forever. The state is the node together with the
+/// position in the instructions, so after `tail` steps it repeats every `len` steps.
+#[derive(Debug, PartialEq, Eq)]
+struct Orbit {
+    tail: usize,
+    len: usize,
+    /// Steps where it is on an end node, before going through the cycle a second time
+    ends: Vec<usize>,
+}
+
+impl Orbit {
+    fn is_end(&self, steps: usize) -> bool {
+        let steps = if steps < self.tail {
+            steps
+        } else {
+            self.tail + (steps - self.tail) % self.len
+        };
+        self.ends.contains(&steps)
+    }
+
+    /// `p` if it is on an end node after `p` steps, and then only at the multiples of `p`,
+    /// which is what the puzzle inputs look like
+    fn period(&self) -> Option<usize> {
+        let p = *self.ends.first()?;
+        let multiples = (1..)
+            .map(|k| k * p)
+            .take_while(|steps| *steps < self.tail + self.len);
+        (p >= self.tail && self.len.is_multiple_of(p) && self.ends.iter().copied().eq(multiples))
+            .then_some(p)
+    }
+}
+
+/// How the ghosts were synchronised
+#[derive(Debug, PartialEq, Eq)]
+enum Method {
+    /// Every ghost is on an end node exactly at the multiples of its period
+    Lcm,
+    /// They met before all of them got into their cycles, checking the steps one by one
+    Tail,
+    /// Needed the chinese remainder theorem on all the ends of the cycles
+    Crt,
+}
+
+impl<'a> Network<'a> {
+    fn orbit(&self, start: &'a str, end: fn(&str) -> bool) -> Orbit {
+        let step = |(node, i): &(&'a str, usize)| (self.next(node, *i), (i + 1) % self.steps.len());
+        let (tail, len) = find_cycle((start, 0), step);
+        let ends = successors(Some((start, 0)), |state| Some(step(state)))
+            .take(tail + len)
+            .positions(|(node, _)| end(node))
+            .collect();
+        Orbit { tail, len, ends }
+    }
+}
+
+/// First step where all the orbits are on an end node at the same time, if there is one
+fn synchronise(orbits: &[Orbit]) -> Result<Option<(usize, Method)>, Overflow> {
+    if let Some(periods) = orbits.iter().map(Orbit::period).collect::<Option<Vec<_>>>() {
+        return Ok(Some((periods.into_iter().fold(1, lcm), Method::Lcm)));
+    }
+    // Before all of them are in their cycles, the ends can be checked one by one
+    let max_tail = orbits.iter().map(|o| o.tail).max().unwrap_or(0);
+    let early = (0..max_tail).find(|steps| orbits.iter().all(|o| o.is_end(*steps)));
+    if let Some(steps) = early {
+        return Ok(Some((steps, Method::Tail)));
+    }
+    // Afterwards, every combination of ends inside the cycles is a system of congruences
+    let solutions = orbits
+        .iter()
+        .map(|o| {
+            o.ends
+                .iter()
+                .filter(|e| **e >= o.tail)
+                .map(|e| (*e as i64, o.len as i64))
+        })
+        .multi_cartesian_product()
+        .map(|congruences| crt(&congruences))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(solutions
+        .into_iter()
+        .flatten()
+        .map(|(x, lcm)| {
+            let (x, lcm) = (x as usize, lcm as usize);
+            // The smallest solution could be in the tail of some orbit
+            x + max_tail.saturating_sub(x).div_ceil(lcm) * lcm
+        })
+        .min()
+        .map(|steps| (steps, Method::Crt)))
+}
+
+fn solve_part2(input: &str) -> (usize, Method) {
     let net = Network::parse(input);
-    net.nodes
+    let orbits = net
+        .nodes
         .keys()
         .filter(|k| k.ends_with('A'))
-        .map(|x| net.cycle_len(x, |x| x.ends_with('Z')))
-        .fold(1, lcm)
+        .map(|x| net.orbit(x, |x| x.ends_with('Z')))
+        .collect_vec();
+    synchronise(&orbits)
+        .expect("The cycles are too long to synchronise the ghosts")
+        .expect("The ghosts are never all at the end at the same time")
+}
+
+pub fn part2(input: &str) -> usize {
+    solve_part2(input).0
 }
 
 #[test]
@@ -119,4 +221,65 @@ fn test() {
     "}),
         6
     );
+
+    assert_eq!(
+        solve_part2(indoc! {"
+        LR
+
+        11A = (11B, XXX)
+        11B = (XXX, 11Z)
+        11Z = (11B, XXX)
+        22A = (22B, XXX)
+        22B = (22C, 22C)
+        22C = (22Z, 22Z)
+        22Z = (22B, 22B)
+        XXX = (XXX, XXX)
+    "})
+        .1,
+        Method::Lcm
+    );
+
+    // The second ghost is on 22Z after 1, 4, 7... steps, so the LCM of the cycles is wrong
+    assert_eq!(
+        solve_part2(indoc! {"
+        L
+
+        11A = (11B, 11B)
+        11B = (11Z, 11Z)
+        11Z = (11B, 11B)
+        22A = (22Z, 22Z)
+        22Z = (22B, 22B)
+        22B = (22C, 22C)
+        22C = (22Z, 22Z)
+    "}),
+        (4, Method::Crt)
+    );
+
+    // Both ghosts are on an end node after 1 step, but the first one never comes back to it
+    assert_eq!(
+        solve_part2(indoc! {"
+        L
+
+        11A = (11Z, 11Z)
+        11Z = (11B, 11B)
+        11B = (11B, 11B)
+        22A = (22Z, 22Z)
+        22Z = (22Z, 22Z)
+    "}),
+        (1, Method::Tail)
+    );
+
+    let orbits = [
+        Orbit {
+            tail: 0,
+            len: 1 << 40,
+            ends: vec![1],
+        },
+        Orbit {
+            tail: 0,
+            len: (1 << 40) + 1,
+            ends: vec![2],
+        },
+    ];
+    assert_eq!(synchronise(&orbits), Err(Overflow));
 }